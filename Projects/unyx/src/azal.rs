@@ -1,141 +1,143 @@
 // Modules
-mod killaura;
-mod mine;
-mod modules;
+pub mod block_names;
+mod block_tags;
+pub mod bridge;
+pub mod chat_control;
+pub mod command;
+pub mod inventory;
+mod item_tags;
+pub mod minimap;
+pub mod modules;
+pub mod recipes;
+mod reconnect;
+pub mod scripts;
+pub mod settings;
+pub mod status;
+pub mod storage;
+mod tasks;
 mod trackers;
+pub mod waypoints;
 pub mod prelude;
 
 // Re-exports
 use color_eyre::Result;
 use modules::ModulesPluginGroup;
 use parking_lot::Mutex;
-use azalea::{pathfinder::goals::{BlockPosGoal, XZGoal}, prelude::*, BlockPos};
+use azalea::{chat::ChatPacket, prelude::*, swarm::prelude::*};
+use bridge::{BridgePlugin, LogChannel};
+use chat_control::ChatControlPlugin;
+use scripts::ScriptsPlugin;
 use trackers::TrackersGroup;
-use std::sync::mpsc::{Receiver, Sender};
-use once_cell::sync::Lazy;
-use killaura::tick_mob_killaura;
-use mine::mine_by_block_id;
+use reconnect::{swarm_handle, BotConnection, SwarmState};
+use status::{BotStatus, StatusPlugin};
+use storage::StoragePlugin;
+use std::sync::{mpsc::{Receiver, Sender}, Arc};
 pub use prelude::*;
+pub use command::{CommandType, Target, TargetedCommand};
+use crate::config::{Config, ModulesConfig};
 
-#[derive(Default, Clone, Component)]
+#[derive(Clone, Component, Default)]
 pub struct State {
-    pub mob_killaura: bool,
-    pub is_on_task: bool
+    pub name: String,
+    /// Module settings to start with, later changes live on the bot entity
+    pub modules: ModulesConfig,
+    /// Password for the server's `/login` plugin, if it has one
+    pub login_password: Option<String>,
+    pub log: LogChannel,
 }
 
 impl State {
-    pub fn new() -> Self {
-        Self { mob_killaura: true, is_on_task: false }
+    pub fn new(name: String, modules: &ModulesConfig, login_password: Option<String>, log: LogChannel) -> Self {
+        Self {
+            name,
+            modules: modules.clone(),
+            login_password,
+            log,
+        }
     }
 }
 
 pub enum ConsoleType {
-    Botlog(String),
-    ServerMsg(String),
-}
-
-#[derive(Clone)]
-pub enum CommandType {
-    Chat(String),
-    Goto(String),
-    Mobkillaura(bool),
-    Mine(String),
+    /// Messages about the swarm itself, not tied to a single bot
+    Console(String),
+    Botlog { bot: String, msg: String },
+    ServerMsg { bot: String, msg: ChatPacket },
+    /// Names in the tab list of `bot`, sent whenever it changes
+    Players { bot: String, names: Vec<String> },
+    /// A fresh map around `bot`, sent while the TUI shows one
+    Minimap { bot: String, map: minimap::Minimap },
 }
 
-// Global variable to store the sender
-static TX_LOG: Lazy<Mutex<Option<Sender<ConsoleType>>>> = Lazy::new(|| Mutex::new(None));
-static RX_INPUT: Lazy<Mutex<Option<Receiver<CommandType>>>> = Lazy::new(|| Mutex::new(None));
-
-async fn handle(bot: Client, event: Event, mut state: State) -> color_eyre::Result<()> {
+async fn handle(bot: Client, event: Event, state: State) -> color_eyre::Result<()> {
     match event {
         Event::Login => {
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-            bot.chat("/login rifaiGG123");
-            bot.chat("/register rifaiGG123 rifaiGG123");
+            state.log.bot_log(&state.name, "logged in");
+            settings::init(&bot, &state.modules);
+            let queued = tasks::queued(&bot);
+            if queued > 0 {
+                state.log.bot_log(&state.name, format!("{queued} task(s) still queued, picking them back up"));
+            }
+            if let Some(password) = &state.login_password {
+                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                bot.chat(&format!("/login {password}"));
+                bot.chat(&format!("/register {password} {password}"));
+            }
         }
         Event::Chat(m) => {
-            let message = m.message().to_ansi();
-            
-            // Send to the channel if available?
-            // why i use ? because that's an option!!! :333
-            if let Some(tx) = &*TX_LOG.lock() {
-                let _ = tx.send(ConsoleType::ServerMsg(message));
-                // let _ = tx.send(ConsoleType::Botlog("GOT MESSAGE".to_string())); // fucking idiot
-            }
+            state.log.send(ConsoleType::ServerMsg { bot: state.name.clone(), msg: m });
         }
         Event::Tick => {
-            if state.mob_killaura {
-                tick_mob_killaura(bot.clone(), state.clone())?;
-            }
+            tasks::poll(&bot, &state.name);
+            scripts::poll(&bot, &state.name);
+            minimap::poll(&bot, &state.name, &state.log);
         }
-        _ => {}
-    }
-    
-    // try debug: Use try_recv() instead of recv() to make it non-blocking
-    // Oh that's work nicee :333
-    let rx_input = RX_INPUT.lock();
-    if let Some(rx) = &*rx_input {
-        match rx.try_recv() {
-            Ok(CommandType::Chat(msg)) => {
-                bot.chat(&msg);
-            }
-            Ok(CommandType::Goto(msg)) => {
-                let msg = msg.split_whitespace().collect::<Vec<_>>();
-                if msg.len() == 3 {
-                    let x = msg[0].parse::<i32>().unwrap();
-                    let y = msg[1].parse::<i32>().unwrap();
-                    let z = msg[2].parse::<i32>().unwrap();
-                    bot.goto(BlockPosGoal(BlockPos::new(x, y, z)));
-                } else if msg.len() == 2 {
-                    let x = msg[0].parse::<i32>().unwrap();
-                    let z = msg[1].parse::<i32>().unwrap();
-                    bot.goto(XZGoal { x: x, z: z});
-                } else {
-                    bot.chat("Invalid coordinates");
-                }
-            }
-            Ok(CommandType::Mobkillaura(enabled)) => {
-                state.mob_killaura = enabled;
-            }
-            Ok(CommandType::Mine(msg)) => {
-                let msg = msg.split_whitespace().collect::<Vec<_>>();
-                let block_id = msg[0].parse::<i32>().unwrap();
-                let quantity = msg[1].parse::<i32>().unwrap();
-                mine_by_block_id(bot.clone(), state.clone(), block_id, quantity)?;
-            }
-            Err(std::sync::mpsc::TryRecvError::Empty) => {
-                // No message available, that's fine :3
-            }
-            Err(_) => {
-                // Channel is disconnected :/
+        Event::AddPlayer(_) | Event::RemovePlayer(_) => {
+            let mut names = bot.tab_list().into_values().map(|info| info.profile.name).collect::<Vec<_>>();
+            names.sort_by_key(|name| name.to_lowercase());
+            state.log.send(ConsoleType::Players { bot: state.name.clone(), names });
+        }
+        Event::Disconnect(reason) => {
+            match reason {
+                Some(reason) => state.log.bot_log(&state.name, format!("disconnected: {reason}")),
+                None => state.log.bot_log(&state.name, "disconnected: connection lost"),
             }
+            tasks::suspend(&bot);
         }
+        _ => {}
     }
     Ok(())
 }
 
-fn init_handler(tx: Sender<ConsoleType>, rx: Receiver<CommandType>) {
-    *TX_LOG.lock() = Some(tx);
-    *RX_INPUT.lock() = Some(rx);
-}
-
 pub async fn start_azalea(
-    address: &str,
-    tx_log: std::sync::mpsc::Sender<ConsoleType>,
-    rx_input: std::sync::mpsc::Receiver<CommandType>,
+    config: Config,
+    login_password: Option<String>,
+    tx_log: Sender<ConsoleType>,
+    tx_status: Sender<BotStatus>,
+    rx_input: Receiver<TargetedCommand>,
 ) -> Result<()> {
-    let account = Account::offline("ItzBtzz");
-    
-    // Initialize the global sender
-    init_handler(tx_log, rx_input);
-
-    ClientBuilder::new()
+    let log = LogChannel::new(tx_log);
+    let swarm_state = SwarmState { reconnect: config.reconnect.clone(), log: log.clone(), ..Default::default() };
+    let mut builder = SwarmBuilder::new()
         .add_plugins(TrackersGroup)
         .add_plugins(ModulesPluginGroup)
+        .add_plugins(StatusPlugin { tx: tx_status })
+        .add_plugins(BridgePlugin {
+            log: log.clone(),
+            input: Arc::new(Mutex::new(rx_input)),
+            accounts: config.accounts.clone(),
+        })
+        .add_plugins(ChatControlPlugin { config: config.chat_control.clone() })
+        .add_plugins(ScriptsPlugin)
+        .add_plugins(StoragePlugin { config: config.storage.clone() })
         .set_handler(handle)
-        .set_state(State::new())
-        .start(account, address)
-        .await
-        .unwrap();
-    Ok(())
-}
\ No newline at end of file
+        .set_swarm_handler(swarm_handle);
+    for account in config.accounts {
+        let state = State::new(account.name.clone(), &config.modules, login_password.clone(), log.clone());
+        swarm_state.bots.lock().insert(account.name.clone(), BotConnection::new(state.clone()));
+        builder = builder.add_account_with_state(Account::offline(&account.name), state);
+    }
+    builder = builder.set_swarm_state(swarm_state);
+
+    let Err(e) = builder.start(config.server.as_str()).await;
+    Err(e.into())
+}
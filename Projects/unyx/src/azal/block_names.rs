@@ -0,0 +1,182 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::OnceLock,
+};
+
+use azalea::{
+    blocks::BlockStates,
+    registry::{Block, Registry},
+};
+
+use super::block_tags::BLOCK_TAGS;
+
+/// How many "did you mean" names are shown for a typo
+const MAX_SUGGESTIONS: usize = 3;
+
+/// `#name` groups from the config, checked before the vanilla tags
+static BLOCK_GROUPS: OnceLock<HashMap<String, Vec<Block>>> = OnceLock::new();
+
+/// The blocks a `mine` command is after, with the name the user typed
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockSelection {
+    pub label: String,
+    pub blocks: Vec<Block>,
+}
+
+impl BlockSelection {
+    /// Every state of every selected block
+    pub fn block_states(&self) -> BlockStates {
+        BlockStates::from(self.blocks.iter().copied().collect::<HashSet<_>>())
+    }
+}
+
+#[derive(Debug)]
+pub enum BlockNameError {
+    UnknownBlock { name: String, suggestions: Vec<String> },
+    UnknownTag { name: String, suggestions: Vec<String> },
+}
+
+impl fmt::Display for BlockNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, name, suggestions) = match self {
+            BlockNameError::UnknownBlock { name, suggestions } => ("block", name, suggestions),
+            BlockNameError::UnknownTag { name, suggestions } => ("block tag or group", name, suggestions),
+        };
+        write!(f, "unknown {kind} `{name}`")?;
+        if !suggestions.is_empty() {
+            write!(f, ", did you mean {}?", suggestions.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BlockNameError {}
+
+/// Resolves `diamond_ore`, `minecraft:diamond_ore`, `#logs` or a `#group`
+/// from the config
+pub fn resolve(query: &str) -> Result<BlockSelection, BlockNameError> {
+    let query = query.trim().to_lowercase();
+    if let Some(tag) = query.strip_prefix('#') {
+        let tag = tag.strip_prefix("minecraft:").unwrap_or(tag);
+        return resolve_tag(tag)
+            .map(|blocks| BlockSelection { label: format!("#{tag}"), blocks })
+            .ok_or_else(|| BlockNameError::UnknownTag {
+                name: format!("#{tag}"),
+                suggestions: suggest(tag, tag_names()).into_iter().map(|name| format!("#{name}")).collect(),
+            });
+    }
+
+    let name = query.strip_prefix("minecraft:").unwrap_or(&query);
+    resolve_block(name)
+        .map(|block| BlockSelection { label: name.to_string(), blocks: vec![block] })
+        .ok_or_else(|| BlockNameError::UnknownBlock {
+            name: name.to_string(),
+            suggestions: suggest(name, block_names()),
+        })
+}
+
+/// Stores the `[block_groups]` of the config, entries may be block names or
+/// vanilla `#tags` but not other groups
+pub fn load_groups(groups: &HashMap<String, Vec<String>>) -> Result<(), String> {
+    let mut resolved = HashMap::new();
+    for (group, entries) in groups {
+        let mut blocks = Vec::new();
+        for entry in entries {
+            let entry = entry.trim().to_lowercase();
+            if let Some(tag) = entry.strip_prefix('#') {
+                let tag = tag.strip_prefix("minecraft:").unwrap_or(tag);
+                let Some(tag_blocks) = vanilla_tag(tag) else {
+                    return Err(format!("block group `{group}` uses unknown tag `#{tag}`"));
+                };
+                blocks.extend(tag_blocks);
+            } else {
+                let name = entry.strip_prefix("minecraft:").unwrap_or(&entry);
+                let Some(block) = resolve_block(name) else {
+                    let suggestions = suggest(name, block_names());
+                    return Err(if suggestions.is_empty() {
+                        format!("block group `{group}` has unknown block `{name}`")
+                    } else {
+                        format!("block group `{group}` has unknown block `{name}`, did you mean {}?", suggestions.join(", "))
+                    });
+                };
+                blocks.push(block);
+            }
+        }
+        blocks.sort_by_key(|block| block.to_u32());
+        blocks.dedup();
+        resolved.insert(group.to_lowercase(), blocks);
+    }
+    BLOCK_GROUPS.set(resolved).map_err(|_| "block groups were already loaded".to_string())
+}
+
+fn resolve_block(name: &str) -> Option<Block> {
+    format!("minecraft:{name}").parse().ok()
+}
+
+fn resolve_tag(name: &str) -> Option<Vec<Block>> {
+    if let Some(blocks) = BLOCK_GROUPS.get().and_then(|groups| groups.get(name)) {
+        return Some(blocks.clone());
+    }
+    vanilla_tag(name)
+}
+
+fn vanilla_tag(name: &str) -> Option<Vec<Block>> {
+    BLOCK_TAGS.iter().find(|(tag, _)| *tag == name).map(|(_, blocks)| {
+        let mut blocks = blocks.iter().copied().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.to_u32());
+        blocks
+    })
+}
+
+/// Every block name without the `minecraft:` namespace
+pub fn block_names() -> Vec<String> {
+    registry_names::<Block>()
+}
+
+/// Every name of a registry like `Item` without the `minecraft:` namespace
+pub fn registry_names<T: Registry + fmt::Display>() -> Vec<String> {
+    (0..)
+        .map_while(T::from_u32)
+        .map(|entry| entry.to_string().trim_start_matches("minecraft:").to_string())
+        .collect()
+}
+
+/// Every user group and vanilla tag name, without the `#`
+pub fn tag_names() -> Vec<String> {
+    let groups = BLOCK_GROUPS.get().into_iter().flat_map(|groups| groups.keys().cloned());
+    groups.chain(BLOCK_TAGS.iter().map(|(tag, _)| tag.to_string())).collect()
+}
+
+/// Closest names to `query`, names containing it come first
+pub fn suggest(query: &str, names: Vec<String>) -> Vec<String> {
+    // allow roughly one typo every three letters
+    let max_distance = (query.len() / 3).max(1);
+    let mut scored = names
+        .into_iter()
+        .filter_map(|name| {
+            if name.contains(query) {
+                return Some((0, name.len(), name));
+            }
+            let distance = levenshtein(query, &name);
+            (distance <= max_distance).then_some((distance, name.len(), name))
+        })
+        .collect::<Vec<_>>();
+    scored.sort();
+    scored.into_iter().take(MAX_SUGGESTIONS).map(|(_, _, name)| name).collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if a == *b { diagonal } else { 1 + diagonal.min(above).min(row[j]) };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
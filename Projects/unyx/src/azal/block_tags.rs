@@ -0,0 +1,200 @@
+// Vanilla block tags by name, so `#logs` can be looked up at runtime.
+// Mirrors `azalea::registry::tags::blocks`.
+
+use std::{collections::HashSet, sync::LazyLock};
+
+use azalea::registry::{tags::blocks::*, Block};
+
+pub static BLOCK_TAGS: &[(&str, &LazyLock<HashSet<Block>>)] = &[
+    ("acacia_logs", &ACACIA_LOGS),
+    ("air", &AIR),
+    ("all_hanging_signs", &ALL_HANGING_SIGNS),
+    ("all_signs", &ALL_SIGNS),
+    ("ancient_city_replaceable", &ANCIENT_CITY_REPLACEABLE),
+    ("animals_spawnable_on", &ANIMALS_SPAWNABLE_ON),
+    ("anvil", &ANVIL),
+    ("armadillo_spawnable_on", &ARMADILLO_SPAWNABLE_ON),
+    ("axolotls_spawnable_on", &AXOLOTLS_SPAWNABLE_ON),
+    ("azalea_grows_on", &AZALEA_GROWS_ON),
+    ("azalea_root_replaceable", &AZALEA_ROOT_REPLACEABLE),
+    ("badlands_terracotta", &BADLANDS_TERRACOTTA),
+    ("bamboo_blocks", &BAMBOO_BLOCKS),
+    ("bamboo_plantable_on", &BAMBOO_PLANTABLE_ON),
+    ("banners", &BANNERS),
+    ("base_stone_nether", &BASE_STONE_NETHER),
+    ("base_stone_overworld", &BASE_STONE_OVERWORLD),
+    ("bats_spawnable_on", &BATS_SPAWNABLE_ON),
+    ("beacon_base_blocks", &BEACON_BASE_BLOCKS),
+    ("beds", &BEDS),
+    ("bee_attractive", &BEE_ATTRACTIVE),
+    ("bee_growables", &BEE_GROWABLES),
+    ("beehives", &BEEHIVES),
+    ("big_dripleaf_placeable", &BIG_DRIPLEAF_PLACEABLE),
+    ("birch_logs", &BIRCH_LOGS),
+    ("blocks_wind_charge_explosions", &BLOCKS_WIND_CHARGE_EXPLOSIONS),
+    ("buttons", &BUTTONS),
+    ("camel_sand_step_sound_blocks", &CAMEL_SAND_STEP_SOUND_BLOCKS),
+    ("camels_spawnable_on", &CAMELS_SPAWNABLE_ON),
+    ("campfires", &CAMPFIRES),
+    ("candle_cakes", &CANDLE_CAKES),
+    ("candles", &CANDLES),
+    ("cauldrons", &CAULDRONS),
+    ("cave_vines", &CAVE_VINES),
+    ("ceiling_hanging_signs", &CEILING_HANGING_SIGNS),
+    ("cherry_logs", &CHERRY_LOGS),
+    ("climbable", &CLIMBABLE),
+    ("coal_ores", &COAL_ORES),
+    ("combination_step_sound_blocks", &COMBINATION_STEP_SOUND_BLOCKS),
+    ("completes_find_tree_tutorial", &COMPLETES_FIND_TREE_TUTORIAL),
+    ("concrete_powder", &CONCRETE_POWDER),
+    ("convertable_to_mud", &CONVERTABLE_TO_MUD),
+    ("copper_ores", &COPPER_ORES),
+    ("coral_blocks", &CORAL_BLOCKS),
+    ("coral_plants", &CORAL_PLANTS),
+    ("corals", &CORALS),
+    ("crimson_stems", &CRIMSON_STEMS),
+    ("crops", &CROPS),
+    ("crystal_sound_blocks", &CRYSTAL_SOUND_BLOCKS),
+    ("dampens_vibrations", &DAMPENS_VIBRATIONS),
+    ("dark_oak_logs", &DARK_OAK_LOGS),
+    ("deepslate_ore_replaceables", &DEEPSLATE_ORE_REPLACEABLES),
+    ("diamond_ores", &DIAMOND_ORES),
+    ("dirt", &DIRT),
+    ("does_not_block_hoppers", &DOES_NOT_BLOCK_HOPPERS),
+    ("doors", &DOORS),
+    ("dragon_immune", &DRAGON_IMMUNE),
+    ("dragon_transparent", &DRAGON_TRANSPARENT),
+    ("dripstone_replaceable_blocks", &DRIPSTONE_REPLACEABLE_BLOCKS),
+    ("dry_vegetation_may_place_on", &DRY_VEGETATION_MAY_PLACE_ON),
+    ("edible_for_sheep", &EDIBLE_FOR_SHEEP),
+    ("emerald_ores", &EMERALD_ORES),
+    ("enchantment_power_provider", &ENCHANTMENT_POWER_PROVIDER),
+    ("enchantment_power_transmitter", &ENCHANTMENT_POWER_TRANSMITTER),
+    ("enderman_holdable", &ENDERMAN_HOLDABLE),
+    ("fall_damage_resetting", &FALL_DAMAGE_RESETTING),
+    ("features_cannot_replace", &FEATURES_CANNOT_REPLACE),
+    ("fence_gates", &FENCE_GATES),
+    ("fences", &FENCES),
+    ("fire", &FIRE),
+    ("flower_pots", &FLOWER_POTS),
+    ("flowers", &FLOWERS),
+    ("foxes_spawnable_on", &FOXES_SPAWNABLE_ON),
+    ("frog_prefer_jump_to", &FROG_PREFER_JUMP_TO),
+    ("frogs_spawnable_on", &FROGS_SPAWNABLE_ON),
+    ("geode_invalid_blocks", &GEODE_INVALID_BLOCKS),
+    ("goats_spawnable_on", &GOATS_SPAWNABLE_ON),
+    ("gold_ores", &GOLD_ORES),
+    ("guarded_by_piglins", &GUARDED_BY_PIGLINS),
+    ("hoglin_repellents", &HOGLIN_REPELLENTS),
+    ("ice", &ICE),
+    ("impermeable", &IMPERMEABLE),
+    ("incorrect_for_diamond_tool", &INCORRECT_FOR_DIAMOND_TOOL),
+    ("incorrect_for_gold_tool", &INCORRECT_FOR_GOLD_TOOL),
+    ("incorrect_for_iron_tool", &INCORRECT_FOR_IRON_TOOL),
+    ("incorrect_for_netherite_tool", &INCORRECT_FOR_NETHERITE_TOOL),
+    ("incorrect_for_stone_tool", &INCORRECT_FOR_STONE_TOOL),
+    ("incorrect_for_wooden_tool", &INCORRECT_FOR_WOODEN_TOOL),
+    ("infiniburn_end", &INFINIBURN_END),
+    ("infiniburn_nether", &INFINIBURN_NETHER),
+    ("infiniburn_overworld", &INFINIBURN_OVERWORLD),
+    ("inside_step_sound_blocks", &INSIDE_STEP_SOUND_BLOCKS),
+    ("invalid_spawn_inside", &INVALID_SPAWN_INSIDE),
+    ("iron_ores", &IRON_ORES),
+    ("jungle_logs", &JUNGLE_LOGS),
+    ("lapis_ores", &LAPIS_ORES),
+    ("lava_pool_stone_cannot_replace", &LAVA_POOL_STONE_CANNOT_REPLACE),
+    ("leaves", &LEAVES),
+    ("logs", &LOGS),
+    ("logs_that_burn", &LOGS_THAT_BURN),
+    ("lush_ground_replaceable", &LUSH_GROUND_REPLACEABLE),
+    ("maintains_farmland", &MAINTAINS_FARMLAND),
+    ("mangrove_logs", &MANGROVE_LOGS),
+    ("mangrove_logs_can_grow_through", &MANGROVE_LOGS_CAN_GROW_THROUGH),
+    ("mangrove_roots_can_grow_through", &MANGROVE_ROOTS_CAN_GROW_THROUGH),
+    ("mineable_axe", &MINEABLE_AXE),
+    ("mineable_hoe", &MINEABLE_HOE),
+    ("mineable_pickaxe", &MINEABLE_PICKAXE),
+    ("mineable_shovel", &MINEABLE_SHOVEL),
+    ("mob_interactable_doors", &MOB_INTERACTABLE_DOORS),
+    ("mooshrooms_spawnable_on", &MOOSHROOMS_SPAWNABLE_ON),
+    ("moss_replaceable", &MOSS_REPLACEABLE),
+    ("mushroom_grow_block", &MUSHROOM_GROW_BLOCK),
+    ("needs_diamond_tool", &NEEDS_DIAMOND_TOOL),
+    ("needs_iron_tool", &NEEDS_IRON_TOOL),
+    ("needs_stone_tool", &NEEDS_STONE_TOOL),
+    ("nether_carver_replaceables", &NETHER_CARVER_REPLACEABLES),
+    ("nylium", &NYLIUM),
+    ("oak_logs", &OAK_LOGS),
+    ("occludes_vibration_signals", &OCCLUDES_VIBRATION_SIGNALS),
+    ("overworld_carver_replaceables", &OVERWORLD_CARVER_REPLACEABLES),
+    ("overworld_natural_logs", &OVERWORLD_NATURAL_LOGS),
+    ("pale_oak_logs", &PALE_OAK_LOGS),
+    ("parrots_spawnable_on", &PARROTS_SPAWNABLE_ON),
+    ("piglin_repellents", &PIGLIN_REPELLENTS),
+    ("planks", &PLANKS),
+    ("plays_ambient_desert_block_sounds", &PLAYS_AMBIENT_DESERT_BLOCK_SOUNDS),
+    ("polar_bears_spawnable_on_alternate", &POLAR_BEARS_SPAWNABLE_ON_ALTERNATE),
+    ("portals", &PORTALS),
+    ("pressure_plates", &PRESSURE_PLATES),
+    ("prevent_mob_spawning_inside", &PREVENT_MOB_SPAWNING_INSIDE),
+    ("rabbits_spawnable_on", &RABBITS_SPAWNABLE_ON),
+    ("rails", &RAILS),
+    ("redstone_ores", &REDSTONE_ORES),
+    ("replaceable", &REPLACEABLE),
+    ("replaceable_by_mushrooms", &REPLACEABLE_BY_MUSHROOMS),
+    ("replaceable_by_trees", &REPLACEABLE_BY_TREES),
+    ("sand", &SAND),
+    ("saplings", &SAPLINGS),
+    ("sculk_replaceable", &SCULK_REPLACEABLE),
+    ("sculk_replaceable_world_gen", &SCULK_REPLACEABLE_WORLD_GEN),
+    ("shulker_boxes", &SHULKER_BOXES),
+    ("signs", &SIGNS),
+    ("slabs", &SLABS),
+    ("small_dripleaf_placeable", &SMALL_DRIPLEAF_PLACEABLE),
+    ("small_flowers", &SMALL_FLOWERS),
+    ("smelts_to_glass", &SMELTS_TO_GLASS),
+    ("snaps_goat_horn", &SNAPS_GOAT_HORN),
+    ("sniffer_diggable_block", &SNIFFER_DIGGABLE_BLOCK),
+    ("sniffer_egg_hatch_boost", &SNIFFER_EGG_HATCH_BOOST),
+    ("snow", &SNOW),
+    ("snow_layer_can_survive_on", &SNOW_LAYER_CAN_SURVIVE_ON),
+    ("snow_layer_cannot_survive_on", &SNOW_LAYER_CANNOT_SURVIVE_ON),
+    ("soul_fire_base_blocks", &SOUL_FIRE_BASE_BLOCKS),
+    ("soul_speed_blocks", &SOUL_SPEED_BLOCKS),
+    ("spruce_logs", &SPRUCE_LOGS),
+    ("stairs", &STAIRS),
+    ("standing_signs", &STANDING_SIGNS),
+    ("stone_bricks", &STONE_BRICKS),
+    ("stone_buttons", &STONE_BUTTONS),
+    ("stone_ore_replaceables", &STONE_ORE_REPLACEABLES),
+    ("stone_pressure_plates", &STONE_PRESSURE_PLATES),
+    ("strider_warm_blocks", &STRIDER_WARM_BLOCKS),
+    ("sword_efficient", &SWORD_EFFICIENT),
+    ("sword_instantly_mines", &SWORD_INSTANTLY_MINES),
+    ("terracotta", &TERRACOTTA),
+    ("trail_ruins_replaceable", &TRAIL_RUINS_REPLACEABLE),
+    ("trapdoors", &TRAPDOORS),
+    ("underwater_bonemeals", &UNDERWATER_BONEMEALS),
+    ("unstable_bottom_center", &UNSTABLE_BOTTOM_CENTER),
+    ("valid_spawn", &VALID_SPAWN),
+    ("vibration_resonators", &VIBRATION_RESONATORS),
+    ("wall_corals", &WALL_CORALS),
+    ("wall_hanging_signs", &WALL_HANGING_SIGNS),
+    ("wall_post_override", &WALL_POST_OVERRIDE),
+    ("wall_signs", &WALL_SIGNS),
+    ("walls", &WALLS),
+    ("warped_stems", &WARPED_STEMS),
+    ("wart_blocks", &WART_BLOCKS),
+    ("wither_immune", &WITHER_IMMUNE),
+    ("wither_summon_base_blocks", &WITHER_SUMMON_BASE_BLOCKS),
+    ("wolves_spawnable_on", &WOLVES_SPAWNABLE_ON),
+    ("wooden_buttons", &WOODEN_BUTTONS),
+    ("wooden_doors", &WOODEN_DOORS),
+    ("wooden_fences", &WOODEN_FENCES),
+    ("wooden_pressure_plates", &WOODEN_PRESSURE_PLATES),
+    ("wooden_slabs", &WOODEN_SLABS),
+    ("wooden_stairs", &WOODEN_STAIRS),
+    ("wooden_trapdoors", &WOODEN_TRAPDOORS),
+    ("wool", &WOOL),
+    ("wool_carpets", &WOOL_CARPETS),
+];
@@ -0,0 +1,193 @@
+// The link between the TUI and the swarm. Commands typed in the TUI come in
+// over a channel that is drained every tick into `BotCommand` events, so any
+// system can react to them, and bot log lines go back out as `BotLog` events.
+
+use std::sync::{
+    mpsc::{Receiver, Sender},
+    Arc,
+};
+
+use azalea::{
+    app::{App, Plugin, Update},
+    chat::SendChatEvent,
+    ecs::prelude::*,
+    entity::LocalEntity,
+    prelude::*,
+    GameProfileComponent,
+};
+use parking_lot::Mutex;
+
+use super::{
+    command::{CommandType, Target, TargetedCommand},
+    inventory,
+    minimap,
+    settings,
+    tasks,
+    ConsoleType,
+};
+use crate::config::AccountConfig;
+
+pub struct BridgePlugin {
+    pub log: LogChannel,
+    pub input: Arc<Mutex<Receiver<TargetedCommand>>>,
+    /// For resolving group names of `@target`
+    pub accounts: Vec<AccountConfig>,
+}
+
+impl Plugin for BridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.log.clone())
+            .insert_resource(CommandChannel(self.input.clone()))
+            .insert_resource(Accounts(self.accounts.clone()))
+            .init_resource::<QueuedCommands>()
+            .add_event::<BotCommand>()
+            .add_event::<BotLog>()
+            .add_systems(
+                GameTick,
+                (
+                    settings::handle_commands,
+                    (tasks::add_queues, tasks::handle_commands).chain(),
+                    inventory::handle_commands,
+                    minimap::handle_commands,
+                    Self::handle_chat,
+                )
+                    .after(Self::drain_commands),
+            )
+            .add_systems(GameTick, Self::drain_commands)
+            // log lines are written from GameTick and Update systems alike,
+            // events only live for two updates so they are read every update
+            .add_systems(Update, Self::forward_logs);
+    }
+}
+
+/// Sends lines to the TUI, cloned into the handler, the task runners and
+/// everything else that runs outside the ECS
+#[derive(Clone, Resource)]
+pub struct LogChannel(Sender<ConsoleType>);
+
+impl LogChannel {
+    pub fn new(tx: Sender<ConsoleType>) -> Self {
+        Self(tx)
+    }
+
+    pub fn send(&self, entry: ConsoleType) {
+        let _ = self.0.send(entry);
+    }
+
+    /// Write a line to the Bot Log tab of `bot`
+    pub fn bot_log(&self, bot: &str, msg: impl Into<String>) {
+        self.send(ConsoleType::Botlog { bot: bot.to_string(), msg: msg.into() });
+    }
+
+    /// Write a line about the swarm itself
+    pub fn console(&self, msg: impl Into<String>) {
+        self.send(ConsoleType::Console(msg.into()));
+    }
+}
+
+/// Goes nowhere, for states that are built before the swarm is
+impl Default for LogChannel {
+    fn default() -> Self {
+        Self(std::sync::mpsc::channel().0)
+    }
+}
+
+/// Commands typed in the TUI, waiting for the next tick
+#[derive(Resource)]
+pub struct CommandChannel(Arc<Mutex<Receiver<TargetedCommand>>>);
+
+/// Every account of the swarm, for resolving `@target`
+#[derive(Resource)]
+pub struct Accounts(pub Vec<AccountConfig>);
+
+/// Commands that came up inside the ECS, e.g. from chat, sent along with the
+/// TUI's on the next tick
+#[derive(Default, Resource)]
+pub struct QueuedCommands(pub Vec<BotCommand>);
+
+/// A command for one bot, with its `@target` already resolved
+#[derive(Event, Clone, Debug)]
+pub struct BotCommand {
+    pub entity: Entity,
+    pub bot: String,
+    pub command: CommandType,
+    /// Player who sent it in game, what the command logs is whispered back
+    pub from: Option<String>,
+}
+
+impl BotCommand {
+    /// A log line about this command, also whispered to whoever sent it
+    pub fn reply(&self, msg: impl Into<String>) -> BotLog {
+        BotLog {
+            bot: self.bot.clone(),
+            msg: msg.into(),
+            whisper: self.from.clone().map(|player| (self.entity, player)),
+        }
+    }
+}
+
+/// A line for the Bot Log tab of `bot`, for systems that have no
+/// `LogChannel` at hand
+#[derive(Event, Clone, Debug)]
+pub struct BotLog {
+    pub bot: String,
+    pub msg: String,
+    /// Bot entity and player to also whisper the line to
+    pub whisper: Option<(Entity, String)>,
+}
+
+impl BotLog {
+    pub fn new(bot: &str, msg: impl Into<String>) -> Self {
+        Self { bot: bot.to_string(), msg: msg.into(), whisper: None }
+    }
+}
+
+impl BridgePlugin {
+    /// Turns every command waiting in the channel into events for the bots
+    /// it targets
+    pub fn drain_commands(
+        channel: Res<CommandChannel>,
+        accounts: Res<Accounts>,
+        mut queued: ResMut<QueuedCommands>,
+        log: Res<LogChannel>,
+        query: Query<(Entity, &GameProfileComponent), With<LocalEntity>>,
+        mut events: EventWriter<BotCommand>,
+    ) {
+        events.send_batch(queued.0.drain(..));
+        let input = channel.0.lock();
+        while let Ok(TargetedCommand { target, command }) = input.try_recv() {
+            let mut matched = 0;
+            for account in accounts.0.iter().filter(|account| target.matches(account)) {
+                matched += 1;
+                match query.iter().find(|(_, profile)| profile.name == account.name) {
+                    Some((entity, _)) => {
+                        events.send(BotCommand {
+                            entity,
+                            bot: account.name.clone(),
+                            command: command.clone(),
+                            from: None,
+                        });
+                    }
+                    None => log.bot_log(&account.name, "error: not connected yet, dropped the command"),
+                }
+            }
+            if matched == 0 && let Target::Named(name) = &target {
+                log.console(format!("error: no bot or group named `{name}`"));
+            }
+        }
+    }
+
+    fn handle_chat(mut events: EventReader<BotCommand>, mut chat_events: EventWriter<SendChatEvent>) {
+        for event in events.read() {
+            if let CommandType::Chat(content) = &event.command {
+                chat_events.send(SendChatEvent { entity: event.entity, content: content.clone() });
+            }
+        }
+    }
+
+    fn forward_logs(mut events: EventReader<BotLog>, log: Res<LogChannel>) {
+        for BotLog { bot, msg, .. } in events.read() {
+            log.bot_log(bot, msg.clone());
+        }
+    }
+}
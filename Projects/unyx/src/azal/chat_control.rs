@@ -0,0 +1,217 @@
+// Commands from players in game. Whitelisted players whisper a command to a
+// bot or say it in chat behind the prefix, it goes through the same parser
+// as the TUI and the answer is whispered back. Every attempt is logged.
+// Only signed player chat counts, its sender is looked up by UUID in the tab
+// list. System lines are plain text anyone's plugin can make look like chat.
+
+use std::fmt;
+
+use azalea::{
+    app::{App, Plugin, Update},
+    chat::{ChatPacket, ChatReceivedEvent, SendChatEvent},
+    ecs::prelude::*,
+    entity::LocalEntity,
+    prelude::*,
+    GameProfileComponent, TabList,
+};
+use serde::Deserialize;
+
+use super::{
+    bridge::{Accounts, BotCommand, BotLog, QueuedCommands},
+    command::{CommandType, ScriptCommand, Target, TaskCommand, COMMANDS},
+};
+use crate::config::ChatControlConfig;
+
+/// Chat messages can't be longer than this
+const MAX_MESSAGE_LEN: usize = 256;
+
+/// What a whitelisted player may do, each level includes the ones before it
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// `get` and `tasks`
+    View,
+    /// Tasks like `goto`, `follow` and `mine`, and managing the queue
+    Control,
+    /// `set`, `chat` and `run` too
+    Admin,
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Permission::View => write!(f, "view"),
+            Permission::Control => write!(f, "control"),
+            Permission::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl Permission {
+    fn required(command: &CommandType) -> Self {
+        match command {
+            CommandType::Get { .. }
+            | CommandType::Tasks(TaskCommand::List)
+            | CommandType::Scripts(ScriptCommand::List) => Permission::View,
+            CommandType::Goto(_)
+            | CommandType::Mine { .. }
+            | CommandType::Follow { .. }
+            | CommandType::Deposit
+            | CommandType::Craft { .. }
+            | CommandType::Farm { .. }
+            | CommandType::Tasks(_)
+            | CommandType::Scripts(_) => Permission::Control,
+            CommandType::Chat(_)
+            | CommandType::Set { .. }
+            | CommandType::Run { .. }
+            | CommandType::Inventory(_)
+            | CommandType::Minimap(_) => Permission::Admin,
+        }
+    }
+
+    /// Level needed for a command by name, for `help`
+    fn required_by_name(name: &str) -> Self {
+        match name {
+            "get" | "tasks" | "scripts" => Permission::View,
+            "chat" | "set" | "mobkillaura" | "run" => Permission::Admin,
+            _ => Permission::Control,
+        }
+    }
+}
+
+pub struct ChatControlPlugin {
+    pub config: ChatControlConfig,
+}
+
+impl Plugin for ChatControlPlugin {
+    fn build(&self, app: &mut App) {
+        if !self.config.enabled {
+            return;
+        }
+        app.insert_resource(ChatControl(self.config.clone()))
+            .add_systems(Update, (Self::handle_chat, Self::send_replies));
+    }
+}
+
+#[derive(Resource)]
+struct ChatControl(ChatControlConfig);
+
+impl ChatControlPlugin {
+    fn handle_chat(
+        mut events: EventReader<ChatReceivedEvent>,
+        config: Res<ChatControl>,
+        accounts: Res<Accounts>,
+        query: Query<(&GameProfileComponent, &TabList), With<LocalEntity>>,
+        mut queued: ResMut<QueuedCommands>,
+        mut log: EventWriter<BotLog>,
+    ) {
+        let config = &config.0;
+        for ChatReceivedEvent { entity, packet } in events.read() {
+            // a `<Admin> ...` system line proves nothing about who sent it
+            let ChatPacket::Player(chat) = packet else {
+                continue;
+            };
+            let Ok((profile, tab_list)) = query.get(*entity) else {
+                continue;
+            };
+            // the profile name, display names can be nicknames
+            let Some(sender) = tab_list.get(&chat.sender).map(|info| info.profile.name.clone()) else {
+                continue;
+            };
+            let content = packet.content();
+            let Some(account) = accounts.0.iter().find(|account| account.name == profile.name) else {
+                continue;
+            };
+            // bots of the swarm never command each other, or replies could loop
+            if accounts.0.iter().any(|account| account.name.eq_ignore_ascii_case(&sender)) {
+                continue;
+            }
+            let input = match content.strip_prefix(config.prefix.as_str()) {
+                Some(input) => input,
+                // a whisper is meant for the bot anyway
+                None if packet.is_whisper() => &content,
+                None => continue,
+            };
+            // chat is heard by every bot, only the ones addressed answer
+            let input = match Target::split(input) {
+                Ok((target, input)) if target.as_ref().is_none_or(|target| target.matches(account)) => input,
+                Ok(_) => continue,
+                Err(e) => {
+                    log.send(reply(*entity, &profile.name, &sender, format!("error: {e}")));
+                    continue;
+                }
+            };
+
+            let Some(level) = config.permission(&sender) else {
+                let line = format!("ignored chat command from {sender}, not whitelisted: {}", input.trim());
+                log.send(BotLog::new(&profile.name, line));
+                continue;
+            };
+            if input.trim().eq_ignore_ascii_case("help") {
+                let names = COMMANDS
+                    .iter()
+                    .filter(|spec| Permission::required_by_name(spec.name) <= level)
+                    .map(|spec| spec.name)
+                    .collect::<Vec<_>>();
+                log.send(reply(*entity, &profile.name, &sender, format!("commands: {}", names.join(", "))));
+                continue;
+            }
+            let mut command = match input.parse::<CommandType>() {
+                Ok(command) => command,
+                Err(e) => {
+                    log.send(reply(*entity, &profile.name, &sender, format!("error: {e}")));
+                    continue;
+                }
+            };
+            if let CommandType::Follow { player, .. } = &mut command
+                && player.eq_ignore_ascii_case("me")
+            {
+                *player = sender.clone();
+            }
+
+            let required = Permission::required(&command);
+            if required > level {
+                log.send(BotLog::new(
+                    &profile.name,
+                    format!("denied chat command from {sender} ({level}): {}", input.trim()),
+                ));
+                log.send(reply(*entity, &profile.name, &sender, format!("error: that needs {required} permission")));
+                continue;
+            }
+            log.send(BotLog::new(&profile.name, format!("chat command from {sender} ({level}): {}", input.trim())));
+            queued.0.push(BotCommand {
+                entity: *entity,
+                bot: profile.name.clone(),
+                command,
+                from: Some(sender),
+            });
+        }
+    }
+
+    /// Whispers log lines back to the player whose command they are about
+    fn send_replies(
+        mut events: EventReader<BotLog>,
+        config: Res<ChatControl>,
+        mut chat_events: EventWriter<SendChatEvent>,
+    ) {
+        for BotLog { msg, whisper, .. } in events.read() {
+            let Some((entity, player)) = whisper else {
+                continue;
+            };
+            let mut content = format!("/{} {player} {msg}", config.0.whisper_command);
+            if content.len() > MAX_MESSAGE_LEN {
+                let mut end = MAX_MESSAGE_LEN;
+                while !content.is_char_boundary(end) {
+                    end -= 1;
+                }
+                content.truncate(end);
+            }
+            chat_events.send(SendChatEvent { entity: *entity, content });
+        }
+    }
+}
+
+/// An answer that only goes to `player`
+fn reply(entity: Entity, bot: &str, player: &str, msg: String) -> BotLog {
+    BotLog { whisper: Some((entity, player.to_string())), ..BotLog::new(bot, msg) }
+}
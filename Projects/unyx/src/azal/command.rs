@@ -0,0 +1,532 @@
+use std::{fmt, str::FromStr};
+
+use azalea::{registry::Item, BlockPos};
+
+use super::{
+    block_names::{self, BlockSelection},
+    inventory::InventoryAction,
+    minimap::MinimapRequest,
+    recipes,
+    scripts,
+    settings,
+    tasks::{craft, farm, follow, Priority},
+    waypoints,
+};
+use crate::config::AccountConfig;
+
+/// Name, usage and a short description of a command typed in the TUI
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: &'static str,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "chat",
+        usage: "chat <message>",
+        help: "Send a chat message or a /command to the server",
+    },
+    CommandSpec {
+        name: "goto",
+        usage: "goto <x> [y] <z> | goto <waypoint>",
+        help: "Walk to a block, to a column when y is left out, or to a waypoint from the config",
+    },
+    CommandSpec {
+        name: "mobkillaura",
+        usage: "mobkillaura <on|off>",
+        help: "Toggle attacking hostile mobs, short for `set killaura enabled`",
+    },
+    CommandSpec {
+        name: "set",
+        usage: "set <module> <key> <value>",
+        help: "Change a module setting, see `get` for the keys",
+    },
+    CommandSpec {
+        name: "get",
+        usage: "get [module] [key]",
+        help: "Show module settings",
+    },
+    CommandSpec {
+        name: "mine",
+        usage: "mine <block|#tag|#group> <quantity>",
+        help: "Mine the nearest blocks by name (diamond_ore), tag (#logs) or config group",
+    },
+    CommandSpec {
+        name: "follow",
+        usage: "follow <player> [distance]",
+        help: "Keep walking after a player, about `distance` blocks (3) away, until cancelled or they leave",
+    },
+    CommandSpec {
+        name: "deposit",
+        usage: "deposit",
+        help: "Store items by the [storage] rules in the storage chests, or the nearest chest or barrel",
+    },
+    CommandSpec {
+        name: "craft",
+        usage: "craft <item> [count]",
+        help: "Craft an item and what it is made of, at a crafting table for 3x3 recipes",
+    },
+    CommandSpec {
+        name: "farm",
+        usage: "farm <x1> <y1> <z1> <x2> <y2> <z2> | farm <waypoint> <waypoint>",
+        help: "Harvest and replant grown crops between two corners, again every farming.interval ticks",
+    },
+    CommandSpec {
+        name: "tasks",
+        usage: "tasks [pause|resume|cancel <id|all>] [priority <id> <low|normal|high>]",
+        help: "List the task queue, or pause, resume, cancel or reprioritize tasks",
+    },
+    CommandSpec {
+        name: "run",
+        usage: "run <script> [args]",
+        help: "Run a Rhai script from the scripts directory, the words after it are in `args`",
+    },
+    CommandSpec {
+        name: "scripts",
+        usage: "scripts [stop <id|all>]",
+        help: "List the running scripts, or stop them",
+    },
+];
+
+pub fn find_spec(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS.iter().find(|spec| spec.name == name)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GotoTarget {
+    Block(BlockPos),
+    Column { x: i32, z: i32 },
+}
+
+/// Which tasks a `tasks` subcommand applies to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskSelector {
+    All,
+    Id(u32),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TaskCommand {
+    List,
+    Pause(TaskSelector),
+    Resume(TaskSelector),
+    Cancel(TaskSelector),
+    Priority(u32, Priority),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptCommand {
+    List,
+    Stop(TaskSelector),
+}
+
+#[derive(Clone, Debug)]
+pub enum CommandType {
+    Chat(String),
+    Goto(GotoTarget),
+    Set { module: String, key: String, value: String },
+    Get { module: Option<String>, key: Option<String> },
+    Mine { blocks: BlockSelection, quantity: usize },
+    Follow { player: String, distance: f32 },
+    Deposit,
+    Craft { item: Item, count: u32 },
+    /// Two opposite corners of the farm
+    Farm { from: BlockPos, to: BlockPos },
+    Tasks(TaskCommand),
+    Run { script: String, args: Vec<String> },
+    Scripts(ScriptCommand),
+    /// Sent by the inventory widget, there is no text form
+    Inventory(InventoryAction),
+    /// Sent by the TUI when it opens or closes the map of a bot
+    Minimap(Option<MinimapRequest>),
+}
+
+/// Which bots of the swarm a command is meant for
+#[derive(Clone, Debug, PartialEq)]
+pub enum Target {
+    All,
+    /// A bot name or a group name, resolved by the command bridge
+    Named(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct TargetedCommand {
+    pub target: Target,
+    pub command: CommandType,
+}
+
+impl Target {
+    /// Splits a leading `@target` off `input`
+    pub fn split(input: &str) -> Result<(Option<Self>, &str), CommandError> {
+        let input = input.trim();
+        let Some(target) = input.strip_prefix('@') else {
+            return Ok((None, input));
+        };
+        let name = target.split_whitespace().next().unwrap_or_default();
+        if name.is_empty() {
+            return Err(CommandError::MissingTarget);
+        }
+        let target = if name.eq_ignore_ascii_case("all") {
+            Target::All
+        } else {
+            Target::Named(name.to_string())
+        };
+        // skip the `@` and the name itself
+        Ok((Some(target), &input[1 + name.len()..]))
+    }
+
+    /// Whether the bot of `account` is one of the targets
+    pub fn matches(&self, account: &AccountConfig) -> bool {
+        match self {
+            Target::All => true,
+            Target::Named(name) => {
+                account.name.eq_ignore_ascii_case(name)
+                    || account.groups.iter().any(|group| group.eq_ignore_ascii_case(name))
+            }
+        }
+    }
+}
+
+impl TargetedCommand {
+    /// Parses `[@target] <command> [args]`, falling back to `default` when no
+    /// `@target` prefix is given
+    pub fn parse(input: &str, default: Target) -> Result<Self, CommandError> {
+        let (target, rest) = Target::split(input)?;
+        Ok(Self {
+            target: target.unwrap_or(default),
+            command: rest.parse()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    Empty,
+    MissingTarget,
+    Unknown(String),
+    InvalidArgs {
+        usage: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "empty command"),
+            CommandError::MissingTarget => write!(f, "expected a bot or group name after `@`"),
+            CommandError::Unknown(name) => write!(f, "unknown command `{name}`, type `help` for a list"),
+            CommandError::InvalidArgs { usage, reason } => write!(f, "{reason} (usage: {usage})"),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// Positional arguments of one command, used to build typed values
+struct Args<'a> {
+    spec: &'static CommandSpec,
+    words: Vec<&'a str>,
+}
+
+impl Args<'_> {
+    fn invalid(&self, reason: impl Into<String>) -> CommandError {
+        CommandError::InvalidArgs {
+            usage: self.spec.usage,
+            reason: reason.into(),
+        }
+    }
+
+    fn expect_len(&self, allowed: &[usize]) -> Result<(), CommandError> {
+        if allowed.contains(&self.words.len()) {
+            Ok(())
+        } else {
+            Err(self.invalid(format!("wrong number of arguments ({})", self.words.len())))
+        }
+    }
+
+    fn get<T: FromStr>(&self, idx: usize, name: &str) -> Result<T, CommandError> {
+        let word = self.words[idx];
+        word.parse::<T>()
+            .map_err(|_| self.invalid(format!("`{word}` is not a valid {name}")))
+    }
+
+    fn module(&self, idx: usize) -> Result<String, CommandError> {
+        let module = self.words[idx].to_lowercase();
+        if settings::keys_of(&module).is_none() {
+            return Err(self.invalid(format!("unknown module `{module}`, one of {}", settings::MODULES.join(", "))));
+        }
+        Ok(module)
+    }
+
+    /// A module name followed by one of its setting keys
+    fn setting(&self, module_idx: usize, key_idx: usize) -> Result<(String, String), CommandError> {
+        let module = self.module(module_idx)?;
+        let key = self.words[key_idx].to_lowercase();
+        let keys = settings::keys_of(&module).unwrap_or_default();
+        if !keys.contains(&key.as_str()) {
+            return Err(self.invalid(format!("{module} has no setting `{key}`, one of {}", keys.join(", "))));
+        }
+        Ok((module, key))
+    }
+
+    /// `<id|all>` right after a `tasks` action
+    fn task_selector(&self) -> Result<TaskSelector, CommandError> {
+        self.expect_len(&[2])?;
+        if self.words[1].eq_ignore_ascii_case("all") {
+            return Ok(TaskSelector::All);
+        }
+        let id = self.words[1].trim_start_matches('#');
+        id.parse()
+            .map(TaskSelector::Id)
+            .map_err(|_| self.invalid(format!("`{}` is not a task id or `all`", self.words[1])))
+    }
+}
+
+impl FromStr for CommandType {
+    type Err = CommandError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let Some(name) = input.split_whitespace().next() else {
+            return Err(CommandError::Empty);
+        };
+        let rest = input[name.len()..].trim();
+        let name = name.to_lowercase();
+        let spec = find_spec(&name).ok_or_else(|| CommandError::Unknown(name.clone()))?;
+        let args = Args {
+            spec,
+            words: rest.split_whitespace().collect(),
+        };
+
+        match spec.name {
+            "chat" => {
+                if rest.is_empty() {
+                    return Err(args.invalid("nothing to send"));
+                }
+                Ok(CommandType::Chat(rest.to_string()))
+            }
+            "goto" => {
+                args.expect_len(&[1, 2, 3])?;
+                if args.words.len() == 1 {
+                    let name = args.words[0];
+                    let pos = waypoints::get(name).ok_or_else(|| args.invalid(format!("no waypoint named `{name}`")))?;
+                    return Ok(CommandType::Goto(GotoTarget::Block(pos)));
+                }
+                if args.words.len() == 3 {
+                    let x = args.get::<i32>(0, "x coordinate")?;
+                    let y = args.get::<i32>(1, "y coordinate")?;
+                    let z = args.get::<i32>(2, "z coordinate")?;
+                    Ok(CommandType::Goto(GotoTarget::Block(BlockPos::new(x, y, z))))
+                } else {
+                    let x = args.get::<i32>(0, "x coordinate")?;
+                    let z = args.get::<i32>(1, "z coordinate")?;
+                    Ok(CommandType::Goto(GotoTarget::Column { x, z }))
+                }
+            }
+            "mobkillaura" => {
+                args.expect_len(&[1])?;
+                let value = match args.words[0].to_lowercase().as_str() {
+                    "on" | "true" => "on",
+                    "off" | "false" => "off",
+                    other => return Err(args.invalid(format!("expected on or off, got `{other}`"))),
+                };
+                Ok(CommandType::Set {
+                    module: "killaura".into(),
+                    key: "enabled".into(),
+                    value: value.into(),
+                })
+            }
+            "set" => {
+                if args.words.len() < 3 {
+                    return Err(args.invalid("expected a module, a key and a value"));
+                }
+                let (module, key) = args.setting(0, 1)?;
+                // lists may be typed with spaces after the commas
+                let value = args.words[2..].join(" ");
+                Ok(CommandType::Set { module, key, value })
+            }
+            "get" => {
+                args.expect_len(&[0, 1, 2])?;
+                let module = match args.words.first() {
+                    Some(_) if args.words.len() == 2 => {
+                        let (module, key) = args.setting(0, 1)?;
+                        return Ok(CommandType::Get { module: Some(module), key: Some(key) });
+                    }
+                    Some(_) => Some(args.module(0)?),
+                    None => None,
+                };
+                Ok(CommandType::Get { module, key: None })
+            }
+            "mine" => {
+                args.expect_len(&[2])?;
+                let blocks = block_names::resolve(args.words[0]).map_err(|e| args.invalid(e.to_string()))?;
+                let quantity = args.get::<usize>(1, "quantity")?;
+                if quantity == 0 {
+                    return Err(args.invalid("quantity must be at least 1"));
+                }
+                Ok(CommandType::Mine { blocks, quantity })
+            }
+            "follow" => {
+                args.expect_len(&[1, 2])?;
+                let distance = match args.words.len() {
+                    2 => args.get::<f32>(1, "distance")?,
+                    _ => follow::DEFAULT_DISTANCE,
+                };
+                if !(1.0..=follow::MAX_DISTANCE).contains(&distance) {
+                    return Err(args.invalid(format!("distance must be between 1 and {}", follow::MAX_DISTANCE)));
+                }
+                Ok(CommandType::Follow { player: args.words[0].to_string(), distance })
+            }
+            "deposit" => {
+                args.expect_len(&[0])?;
+                Ok(CommandType::Deposit)
+            }
+            "craft" => {
+                args.expect_len(&[1, 2])?;
+                let item = recipes::resolve_item(args.words[0]).map_err(|e| args.invalid(e))?;
+                let count = match args.words.len() {
+                    2 => args.get::<u32>(1, "count")?,
+                    _ => 1,
+                };
+                if !(1..=craft::MAX_COUNT).contains(&count) {
+                    return Err(args.invalid(format!("count must be between 1 and {}", craft::MAX_COUNT)));
+                }
+                Ok(CommandType::Craft { item, count })
+            }
+            "farm" => {
+                args.expect_len(&[2, 6])?;
+                let (from, to) = if args.words.len() == 2 {
+                    let waypoint = |name: &str| {
+                        waypoints::get(name).ok_or_else(|| args.invalid(format!("no waypoint named `{name}`")))
+                    };
+                    (waypoint(args.words[0])?, waypoint(args.words[1])?)
+                } else {
+                    let coordinate = |idx| args.get::<i32>(idx, "coordinate");
+                    let from = BlockPos::new(coordinate(0)?, coordinate(1)?, coordinate(2)?);
+                    (from, BlockPos::new(coordinate(3)?, coordinate(4)?, coordinate(5)?))
+                };
+                let size = |a: i32, b: i32| a.abs_diff(b).saturating_add(1);
+                if size(from.x, to.x) > farm::MAX_WIDTH || size(from.z, to.z) > farm::MAX_WIDTH {
+                    return Err(args.invalid(format!("a farm can be at most {} blocks wide", farm::MAX_WIDTH)));
+                }
+                if size(from.y, to.y) > farm::MAX_HEIGHT {
+                    return Err(args.invalid(format!("a farm can be at most {} blocks high", farm::MAX_HEIGHT)));
+                }
+                Ok(CommandType::Farm { from, to })
+            }
+            "tasks" => {
+                let Some(action) = args.words.first() else {
+                    return Ok(CommandType::Tasks(TaskCommand::List));
+                };
+                let command = match action.to_lowercase().as_str() {
+                    "list" => {
+                        args.expect_len(&[1])?;
+                        TaskCommand::List
+                    }
+                    "pause" => TaskCommand::Pause(args.task_selector()?),
+                    "resume" => TaskCommand::Resume(args.task_selector()?),
+                    "cancel" => TaskCommand::Cancel(args.task_selector()?),
+                    "priority" => {
+                        args.expect_len(&[3])?;
+                        let id = args.get::<u32>(1, "task id")?;
+                        let priority = args.get::<Priority>(2, "priority")?;
+                        TaskCommand::Priority(id, priority)
+                    }
+                    other => return Err(args.invalid(format!("unknown action `{other}`"))),
+                };
+                Ok(CommandType::Tasks(command))
+            }
+            "run" => {
+                let Some(script) = args.words.first() else {
+                    return Err(args.invalid("expected a script name"));
+                };
+                scripts::check_name(script).map_err(|e| args.invalid(e))?;
+                let rest = args.words[1..].iter().map(|word| word.to_string()).collect();
+                Ok(CommandType::Run { script: script.to_string(), args: rest })
+            }
+            "scripts" => {
+                let Some(action) = args.words.first() else {
+                    return Ok(CommandType::Scripts(ScriptCommand::List));
+                };
+                let command = match action.to_lowercase().as_str() {
+                    "list" => {
+                        args.expect_len(&[1])?;
+                        ScriptCommand::List
+                    }
+                    "stop" => ScriptCommand::Stop(args.task_selector()?),
+                    other => return Err(args.invalid(format!("unknown action `{other}`"))),
+                };
+                Ok(CommandType::Scripts(command))
+            }
+            _ => unreachable!("every entry in COMMANDS has a parser"),
+        }
+    }
+}
+
+/// What Tab can turn the word before the cursor into
+pub struct Completion {
+    /// Byte offset in the line where the completed word starts
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+/// Candidates for the last word of `line`, `players` are the names from the
+/// tab list and `bots` the names an `@` prefix can address
+pub fn complete(line: &str, players: &[String], bots: &[String]) -> Completion {
+    let mut start = line.rfind(char::is_whitespace).map_or(0, |idx| idx + 1);
+    let mut words = line[..start].split_whitespace().map(str::to_lowercase).collect::<Vec<_>>();
+    if words.is_empty() && line[start..].starts_with('@') {
+        let targets = bots.iter().cloned().chain(["all".to_string()]).map(|name| format!("@{name}"));
+        return Completion { start, candidates: matching(&line[start..], targets) };
+    }
+    if words.first().is_some_and(|word| word.starts_with('@')) {
+        words.remove(0);
+    }
+
+    let options: Vec<String> = match words.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => COMMANDS.iter().map(|spec| spec.name.to_string()).chain(["help".to_string()]).collect(),
+        ["help"] => COMMANDS.iter().map(|spec| spec.name.to_string()).collect(),
+        ["set" | "get"] => settings::MODULES.iter().map(|module| module.to_string()).collect(),
+        ["set" | "get", module] => {
+            settings::keys_of(module).unwrap_or_default().iter().map(|key| key.to_string()).collect()
+        }
+        ["set", module, key] => {
+            // lists are comma separated, complete the entry after the last comma
+            if let Some(comma) = line[start..].rfind(',') {
+                start += comma + 1;
+            }
+            settings::value_options(module, key)
+        }
+        ["mobkillaura"] => vec!["on".into(), "off".into()],
+        ["mine"] if line[start..].starts_with('#') => {
+            block_names::tag_names().into_iter().map(|tag| format!("#{tag}")).collect()
+        }
+        ["mine"] => block_names::block_names(),
+        ["goto" | "farm"] | ["farm", _] => waypoints::names(),
+        ["craft"] => recipes::item_names(),
+        ["follow"] => players.to_vec(),
+        ["chat", ..] => players.to_vec(),
+        ["tasks"] => ["list", "pause", "resume", "cancel", "priority"].map(String::from).to_vec(),
+        ["tasks", "pause" | "resume" | "cancel"] => vec!["all".into()],
+        ["tasks", "priority", _] => ["low", "normal", "high"].map(String::from).to_vec(),
+        ["run"] => scripts::names(),
+        ["scripts"] => ["list", "stop"].map(String::from).to_vec(),
+        ["scripts", "stop"] => vec!["all".into()],
+        _ => Vec::new(),
+    };
+    Completion { start, candidates: matching(&line[start..], options) }
+}
+
+/// `options` starting with `prefix` ignoring case, sorted and without repeats
+fn matching(prefix: &str, options: impl IntoIterator<Item = String>) -> Vec<String> {
+    let prefix = prefix.to_lowercase();
+    let mut candidates = options
+        .into_iter()
+        .filter(|option| option.to_lowercase().starts_with(&prefix))
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
@@ -0,0 +1,154 @@
+// Inventory snapshots for the TUI and the clicks it asks for. The TUI only
+// knows player menu slot numbers, they are mapped onto whatever menu is open
+// right before clicking, so a container opening in between can't make us
+// click the wrong slot.
+
+use azalea::{
+    ecs::prelude::*,
+    inventory::{
+        operations::{ClickOperation, PickupClick, QuickMoveClick, SwapClick, ThrowClick},
+        ContainerClickEvent,
+        Inventory,
+        ItemStack,
+        Player,
+        SetSelectedHotbarSlotEvent,
+    },
+};
+
+use super::{
+    bridge::{BotCommand, BotLog},
+    command::CommandType,
+};
+
+/// Name without the namespace and count of an item, `None` for an empty slot
+pub type ItemView = Option<(String, i32)>;
+
+pub fn item_view(item: &ItemStack) -> ItemView {
+    (!item.is_empty()).then(|| {
+        let name = item.kind().to_string();
+        (name.trim_start_matches("minecraft:").to_string(), item.count())
+    })
+}
+
+/// What the inventory widget shows for one bot
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InventoryView {
+    /// Every slot of the player menu, by its slot number
+    pub player: Vec<ItemView>,
+    pub selected_hotbar: u8,
+    /// Title and slots of the open container, without the player's part
+    pub container: Option<(String, Vec<ItemView>)>,
+}
+
+impl InventoryView {
+    pub fn new(inventory: &Inventory) -> Self {
+        let mut player = inventory.inventory_menu.slots().iter().map(item_view).collect::<Vec<_>>();
+        let container = inventory.container_menu.as_ref().map(|menu| {
+            // the server only keeps the open menu up to date
+            let start = *menu.player_slots_range().start();
+            for (slot, item) in Player::INVENTORY_SLOTS.zip(&menu.slots()[start..]) {
+                player[slot] = item_view(item);
+            }
+            let title = inventory.container_menu_title.as_ref().map_or_else(|| "Container".to_string(), |t| t.to_string());
+            (title, menu.contents().iter().map(item_view).collect())
+        });
+        Self { player, selected_hotbar: inventory.selected_hotbar_slot, container }
+    }
+}
+
+/// A slot as the TUI sees it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SlotRef {
+    /// Slot number in the player menu, 5..=8 armor, 9..=35 the main
+    /// inventory, 36..=44 the hotbar and 45 the offhand
+    Player(usize),
+    /// Slot of the open container
+    Container(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum InventoryAction {
+    Swap(SlotRef, SlotRef),
+    /// Throws the whole stack
+    Drop(SlotRef),
+    /// Hotbar slot to hold, 0..=8
+    SelectHotbar(u8),
+    /// Shift-click, into the open container or out of it
+    QuickMove(SlotRef),
+}
+
+/// Sends the clicks for inventory actions, logging why when one can't be
+/// done
+pub fn handle_commands(
+    mut events: EventReader<BotCommand>,
+    query: Query<&Inventory>,
+    mut click_events: EventWriter<ContainerClickEvent>,
+    mut hotbar_events: EventWriter<SetSelectedHotbarSlotEvent>,
+    mut log: EventWriter<BotLog>,
+) {
+    for event in events.read() {
+        let BotCommand { entity, command, .. } = event;
+        let (CommandType::Inventory(action), Ok(inventory)) = (command, query.get(*entity)) else {
+            continue;
+        };
+        let entity = *entity;
+        if let InventoryAction::SelectHotbar(slot) = action {
+            hotbar_events.send(SetSelectedHotbarSlotEvent { entity, slot: (*slot).min(8) });
+            continue;
+        }
+        match clicks(inventory, action) {
+            Ok(clicks) => {
+                for operation in clicks {
+                    click_events.send(ContainerClickEvent { entity, window_id: inventory.id, operation });
+                }
+            }
+            Err(e) => {
+                log.send(event.reply(format!("error: {e}")));
+            }
+        }
+    }
+}
+
+fn clicks(inventory: &Inventory, action: &InventoryAction) -> Result<Vec<ClickOperation>, String> {
+    Ok(match *action {
+        InventoryAction::Swap(from, to) if from == to => Vec::new(),
+        InventoryAction::Swap(from, to) => {
+            if !inventory.carried.is_empty() {
+                return Err("the cursor is still holding an item".into());
+            }
+            let hotbar = |slot| match slot {
+                SlotRef::Player(slot) if Player::HOTBAR_SLOTS.contains(&slot) => {
+                    u8::try_from(slot - Player::HOTBAR_SLOTS.start()).ok()
+                }
+                _ => None,
+            };
+            match (hotbar(from), hotbar(to)) {
+                // the number keys swap in one click
+                (_, Some(target_slot)) => vec![SwapClick { source_slot: menu_slot(inventory, from)?, target_slot }.into()],
+                (Some(target_slot), None) => vec![SwapClick { source_slot: menu_slot(inventory, to)?, target_slot }.into()],
+                (None, None) => {
+                    let (from, to) = (menu_slot(inventory, from)?, menu_slot(inventory, to)?);
+                    [from, to, from].map(|slot| PickupClick::Left { slot: Some(slot) }.into()).to_vec()
+                }
+            }
+        }
+        InventoryAction::Drop(slot) => vec![ThrowClick::All { slot: menu_slot(inventory, slot)? }.into()],
+        InventoryAction::QuickMove(slot) => vec![QuickMoveClick::Left { slot: menu_slot(inventory, slot)? }.into()],
+        InventoryAction::SelectHotbar(_) => Vec::new(),
+    })
+}
+
+/// Number of `slot` in the menu that is open right now
+fn menu_slot(inventory: &Inventory, slot: SlotRef) -> Result<u16, String> {
+    let idx = match (&inventory.container_menu, slot) {
+        (None, SlotRef::Player(idx)) => idx,
+        (None, SlotRef::Container(_)) => return Err("no container is open anymore".into()),
+        (Some(menu), SlotRef::Player(idx)) if Player::INVENTORY_SLOTS.contains(&idx) => {
+            menu.player_slots_range().start() + idx - Player::INVENTORY_SLOTS.start()
+        }
+        (Some(_), SlotRef::Player(_)) => return Err("armor and offhand can't be reached while a container is open".into()),
+        (Some(menu), SlotRef::Container(idx)) if idx < *menu.player_slots_range().start() => idx,
+        (Some(_), SlotRef::Container(idx)) => return Err(format!("the container has no slot {idx}")),
+    };
+    u16::try_from(idx).map_err(|_| format!("no slot {idx}"))
+}
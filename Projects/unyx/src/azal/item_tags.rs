@@ -0,0 +1,185 @@
+// Vanilla item tags by name, so recipes can name `#minecraft:planks`.
+// Mirrors `azalea::registry::tags::items`.
+
+use std::{collections::HashSet, sync::LazyLock};
+
+use azalea::registry::{tags::items::*, Item};
+
+pub static ITEM_TAGS: &[(&str, &LazyLock<HashSet<Item>>)] = &[
+    ("acacia_logs", &ACACIA_LOGS),
+    ("anvil", &ANVIL),
+    ("armadillo_food", &ARMADILLO_FOOD),
+    ("arrows", &ARROWS),
+    ("axes", &AXES),
+    ("axolotl_food", &AXOLOTL_FOOD),
+    ("bamboo_blocks", &BAMBOO_BLOCKS),
+    ("banners", &BANNERS),
+    ("beacon_payment_items", &BEACON_PAYMENT_ITEMS),
+    ("beds", &BEDS),
+    ("bee_food", &BEE_FOOD),
+    ("birch_logs", &BIRCH_LOGS),
+    ("boats", &BOATS),
+    ("book_cloning_target", &BOOK_CLONING_TARGET),
+    ("bookshelf_books", &BOOKSHELF_BOOKS),
+    ("breaks_decorated_pots", &BREAKS_DECORATED_POTS),
+    ("brewing_fuel", &BREWING_FUEL),
+    ("bundles", &BUNDLES),
+    ("buttons", &BUTTONS),
+    ("camel_food", &CAMEL_FOOD),
+    ("candles", &CANDLES),
+    ("cat_food", &CAT_FOOD),
+    ("cherry_logs", &CHERRY_LOGS),
+    ("chest_armor", &CHEST_ARMOR),
+    ("chest_boats", &CHEST_BOATS),
+    ("chicken_food", &CHICKEN_FOOD),
+    ("cluster_max_harvestables", &CLUSTER_MAX_HARVESTABLES),
+    ("coal_ores", &COAL_ORES),
+    ("coals", &COALS),
+    ("compasses", &COMPASSES),
+    ("completes_find_tree_tutorial", &COMPLETES_FIND_TREE_TUTORIAL),
+    ("copper_ores", &COPPER_ORES),
+    ("cow_food", &COW_FOOD),
+    ("creeper_drop_music_discs", &CREEPER_DROP_MUSIC_DISCS),
+    ("creeper_igniters", &CREEPER_IGNITERS),
+    ("crimson_stems", &CRIMSON_STEMS),
+    ("dampens_vibrations", &DAMPENS_VIBRATIONS),
+    ("dark_oak_logs", &DARK_OAK_LOGS),
+    ("decorated_pot_ingredients", &DECORATED_POT_INGREDIENTS),
+    ("decorated_pot_sherds", &DECORATED_POT_SHERDS),
+    ("diamond_ores", &DIAMOND_ORES),
+    ("diamond_tool_materials", &DIAMOND_TOOL_MATERIALS),
+    ("dirt", &DIRT),
+    ("doors", &DOORS),
+    ("drowned_preferred_weapons", &DROWNED_PREFERRED_WEAPONS),
+    ("duplicates_allays", &DUPLICATES_ALLAYS),
+    ("dyeable", &DYEABLE),
+    ("eggs", &EGGS),
+    ("emerald_ores", &EMERALD_ORES),
+    ("enchantable_armor", &ENCHANTABLE_ARMOR),
+    ("enchantable_bow", &ENCHANTABLE_BOW),
+    ("enchantable_chest_armor", &ENCHANTABLE_CHEST_ARMOR),
+    ("enchantable_crossbow", &ENCHANTABLE_CROSSBOW),
+    ("enchantable_durability", &ENCHANTABLE_DURABILITY),
+    ("enchantable_equippable", &ENCHANTABLE_EQUIPPABLE),
+    ("enchantable_fire_aspect", &ENCHANTABLE_FIRE_ASPECT),
+    ("enchantable_fishing", &ENCHANTABLE_FISHING),
+    ("enchantable_foot_armor", &ENCHANTABLE_FOOT_ARMOR),
+    ("enchantable_head_armor", &ENCHANTABLE_HEAD_ARMOR),
+    ("enchantable_leg_armor", &ENCHANTABLE_LEG_ARMOR),
+    ("enchantable_mace", &ENCHANTABLE_MACE),
+    ("enchantable_mining", &ENCHANTABLE_MINING),
+    ("enchantable_mining_loot", &ENCHANTABLE_MINING_LOOT),
+    ("enchantable_sharp_weapon", &ENCHANTABLE_SHARP_WEAPON),
+    ("enchantable_sword", &ENCHANTABLE_SWORD),
+    ("enchantable_trident", &ENCHANTABLE_TRIDENT),
+    ("enchantable_vanishing", &ENCHANTABLE_VANISHING),
+    ("enchantable_weapon", &ENCHANTABLE_WEAPON),
+    ("fence_gates", &FENCE_GATES),
+    ("fences", &FENCES),
+    ("fishes", &FISHES),
+    ("flowers", &FLOWERS),
+    ("foot_armor", &FOOT_ARMOR),
+    ("fox_food", &FOX_FOOD),
+    ("freeze_immune_wearables", &FREEZE_IMMUNE_WEARABLES),
+    ("frog_food", &FROG_FOOD),
+    ("furnace_minecart_fuel", &FURNACE_MINECART_FUEL),
+    ("gaze_disguise_equipment", &GAZE_DISGUISE_EQUIPMENT),
+    ("goat_food", &GOAT_FOOD),
+    ("gold_ores", &GOLD_ORES),
+    ("gold_tool_materials", &GOLD_TOOL_MATERIALS),
+    ("hanging_signs", &HANGING_SIGNS),
+    ("head_armor", &HEAD_ARMOR),
+    ("hoes", &HOES),
+    ("hoglin_food", &HOGLIN_FOOD),
+    ("horse_food", &HORSE_FOOD),
+    ("horse_tempt_items", &HORSE_TEMPT_ITEMS),
+    ("ignored_by_piglin_babies", &IGNORED_BY_PIGLIN_BABIES),
+    ("iron_ores", &IRON_ORES),
+    ("iron_tool_materials", &IRON_TOOL_MATERIALS),
+    ("jungle_logs", &JUNGLE_LOGS),
+    ("lapis_ores", &LAPIS_ORES),
+    ("leaves", &LEAVES),
+    ("lectern_books", &LECTERN_BOOKS),
+    ("leg_armor", &LEG_ARMOR),
+    ("llama_food", &LLAMA_FOOD),
+    ("llama_tempt_items", &LLAMA_TEMPT_ITEMS),
+    ("logs", &LOGS),
+    ("logs_that_burn", &LOGS_THAT_BURN),
+    ("mangrove_logs", &MANGROVE_LOGS),
+    ("map_invisibility_equipment", &MAP_INVISIBILITY_EQUIPMENT),
+    ("meat", &MEAT),
+    ("netherite_tool_materials", &NETHERITE_TOOL_MATERIALS),
+    ("non_flammable_wood", &NON_FLAMMABLE_WOOD),
+    ("noteblock_top_instruments", &NOTEBLOCK_TOP_INSTRUMENTS),
+    ("oak_logs", &OAK_LOGS),
+    ("ocelot_food", &OCELOT_FOOD),
+    ("pale_oak_logs", &PALE_OAK_LOGS),
+    ("panda_eats_from_ground", &PANDA_EATS_FROM_GROUND),
+    ("panda_food", &PANDA_FOOD),
+    ("parrot_food", &PARROT_FOOD),
+    ("parrot_poisonous_food", &PARROT_POISONOUS_FOOD),
+    ("pickaxes", &PICKAXES),
+    ("pig_food", &PIG_FOOD),
+    ("piglin_food", &PIGLIN_FOOD),
+    ("piglin_loved", &PIGLIN_LOVED),
+    ("piglin_preferred_weapons", &PIGLIN_PREFERRED_WEAPONS),
+    ("piglin_repellents", &PIGLIN_REPELLENTS),
+    ("piglin_safe_armor", &PIGLIN_SAFE_ARMOR),
+    ("pillager_preferred_weapons", &PILLAGER_PREFERRED_WEAPONS),
+    ("planks", &PLANKS),
+    ("rabbit_food", &RABBIT_FOOD),
+    ("rails", &RAILS),
+    ("redstone_ores", &REDSTONE_ORES),
+    ("repairs_chain_armor", &REPAIRS_CHAIN_ARMOR),
+    ("repairs_diamond_armor", &REPAIRS_DIAMOND_ARMOR),
+    ("repairs_gold_armor", &REPAIRS_GOLD_ARMOR),
+    ("repairs_iron_armor", &REPAIRS_IRON_ARMOR),
+    ("repairs_leather_armor", &REPAIRS_LEATHER_ARMOR),
+    ("repairs_netherite_armor", &REPAIRS_NETHERITE_ARMOR),
+    ("repairs_turtle_helmet", &REPAIRS_TURTLE_HELMET),
+    ("repairs_wolf_armor", &REPAIRS_WOLF_ARMOR),
+    ("sand", &SAND),
+    ("saplings", &SAPLINGS),
+    ("sheep_food", &SHEEP_FOOD),
+    ("shovels", &SHOVELS),
+    ("shulker_boxes", &SHULKER_BOXES),
+    ("signs", &SIGNS),
+    ("skeleton_preferred_weapons", &SKELETON_PREFERRED_WEAPONS),
+    ("skulls", &SKULLS),
+    ("slabs", &SLABS),
+    ("small_flowers", &SMALL_FLOWERS),
+    ("smelts_to_glass", &SMELTS_TO_GLASS),
+    ("sniffer_food", &SNIFFER_FOOD),
+    ("soul_fire_base_blocks", &SOUL_FIRE_BASE_BLOCKS),
+    ("spruce_logs", &SPRUCE_LOGS),
+    ("stairs", &STAIRS),
+    ("stone_bricks", &STONE_BRICKS),
+    ("stone_buttons", &STONE_BUTTONS),
+    ("stone_crafting_materials", &STONE_CRAFTING_MATERIALS),
+    ("stone_tool_materials", &STONE_TOOL_MATERIALS),
+    ("strider_food", &STRIDER_FOOD),
+    ("strider_tempt_items", &STRIDER_TEMPT_ITEMS),
+    ("swords", &SWORDS),
+    ("terracotta", &TERRACOTTA),
+    ("trapdoors", &TRAPDOORS),
+    ("trim_materials", &TRIM_MATERIALS),
+    ("trimmable_armor", &TRIMMABLE_ARMOR),
+    ("turtle_food", &TURTLE_FOOD),
+    ("villager_picks_up", &VILLAGER_PICKS_UP),
+    ("villager_plantable_seeds", &VILLAGER_PLANTABLE_SEEDS),
+    ("walls", &WALLS),
+    ("warped_stems", &WARPED_STEMS),
+    ("wart_blocks", &WART_BLOCKS),
+    ("wither_skeleton_disliked_weapons", &WITHER_SKELETON_DISLIKED_WEAPONS),
+    ("wolf_food", &WOLF_FOOD),
+    ("wooden_buttons", &WOODEN_BUTTONS),
+    ("wooden_doors", &WOODEN_DOORS),
+    ("wooden_fences", &WOODEN_FENCES),
+    ("wooden_pressure_plates", &WOODEN_PRESSURE_PLATES),
+    ("wooden_slabs", &WOODEN_SLABS),
+    ("wooden_stairs", &WOODEN_STAIRS),
+    ("wooden_tool_materials", &WOODEN_TOOL_MATERIALS),
+    ("wooden_trapdoors", &WOODEN_TRAPDOORS),
+    ("wool", &WOOL),
+    ("wool_carpets", &WOOL_CARPETS),
+];
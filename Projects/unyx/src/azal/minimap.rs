@@ -0,0 +1,299 @@
+// Top-down map around a bot for the TUI. It is only built while the TUI has
+// it open, every few ticks, from the loaded chunks and the same entities
+// killaura picks its targets from.
+
+use azalea::{
+    blocks::BlockState,
+    core::position::{ChunkBlockPos, ChunkPos},
+    ecs::prelude::*,
+    entity::EntityUuid,
+    prelude::*,
+    registry::{tags::blocks::*, Block, EntityKind},
+    world::{heightmap::HeightmapKind, Instance, InstanceName},
+    BlockPos,
+};
+
+use super::{
+    bridge::{BotCommand, LogChannel},
+    command::CommandType,
+    modules::killaura::{TargetData, TargetFilter},
+    trackers::game_tick::GameTicks,
+    ConsoleType,
+};
+
+/// Ticks between two maps
+const INTERVAL_TICKS: u128 = 10;
+/// How far below an open cell of a slice still counts as its floor
+const FLOOR_DEPTH: i32 = 2;
+
+/// What the TUI asked for
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MinimapRequest {
+    /// Cells from the bot to the edge of the map
+    pub radius: u32,
+    /// Blocks across one cell
+    pub scale: u32,
+    /// Map the blocks this far above the feet instead of the top blocks,
+    /// for seeing around in caves
+    pub slice: Option<i32>,
+}
+
+/// Kept on the bot entity while the map is open
+#[derive(Component)]
+struct MinimapState {
+    request: MinimapRequest,
+    next_at: u128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Terrain {
+    Unloaded,
+    /// Air all the way down, or a drop in a slice
+    Empty,
+    /// Open space with ground right below it, only in slices
+    Floor,
+    Water,
+    Lava,
+    Grass,
+    Leaves,
+    Dirt,
+    Sand,
+    Stone,
+    Wood,
+    Snow,
+    Crops,
+    Ore(Ore),
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ore {
+    Coal,
+    Copper,
+    Iron,
+    Gold,
+    Redstone,
+    Lapis,
+    Emerald,
+    Diamond,
+    Quartz,
+    Debris,
+}
+
+impl Terrain {
+    fn of(state: BlockState) -> Self {
+        let block = Block::from(state);
+        let ore = [
+            (&COAL_ORES, Ore::Coal),
+            (&COPPER_ORES, Ore::Copper),
+            (&IRON_ORES, Ore::Iron),
+            (&GOLD_ORES, Ore::Gold),
+            (&REDSTONE_ORES, Ore::Redstone),
+            (&LAPIS_ORES, Ore::Lapis),
+            (&EMERALD_ORES, Ore::Emerald),
+            (&DIAMOND_ORES, Ore::Diamond),
+        ]
+        .into_iter()
+        .find(|(tag, _)| tag.contains(&block));
+        if let Some((_, ore)) = ore {
+            return Terrain::Ore(ore);
+        }
+        match block {
+            Block::NetherQuartzOre => Terrain::Ore(Ore::Quartz),
+            Block::AncientDebris => Terrain::Ore(Ore::Debris),
+            Block::Air | Block::CaveAir | Block::VoidAir => Terrain::Empty,
+            Block::Water | Block::Kelp | Block::KelpPlant | Block::Seagrass | Block::TallSeagrass | Block::BubbleColumn => {
+                Terrain::Water
+            }
+            Block::Lava => Terrain::Lava,
+            Block::GrassBlock
+            | Block::ShortGrass
+            | Block::TallGrass
+            | Block::Fern
+            | Block::LargeFern
+            | Block::MossBlock
+            | Block::MossCarpet => Terrain::Grass,
+            Block::Gravel => Terrain::Sand,
+            Block::Cobblestone | Block::Bedrock => Terrain::Stone,
+            _ if LEAVES.contains(&block) => Terrain::Leaves,
+            _ if LOGS.contains(&block) || PLANKS.contains(&block) => Terrain::Wood,
+            _ if CROPS.contains(&block) => Terrain::Crops,
+            _ if SAND.contains(&block) => Terrain::Sand,
+            _ if DIRT.contains(&block) => Terrain::Dirt,
+            _ if SNOW.contains(&block) || ICE.contains(&block) => Terrain::Snow,
+            _ if BASE_STONE_OVERWORLD.contains(&block) || BASE_STONE_NETHER.contains(&block) => Terrain::Stone,
+            _ => Terrain::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapCell {
+    pub terrain: Terrain,
+    /// Height of the mapped block relative to the bot's feet
+    pub height: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MarkerKind {
+    Player,
+    Monster,
+    Animal,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    /// Cell of the map, from the top left corner
+    pub row: usize,
+    pub col: usize,
+    pub kind: MarkerKind,
+    /// Player name or entity kind
+    pub label: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Minimap {
+    pub center: BlockPos,
+    pub yaw: f32,
+    pub request: MinimapRequest,
+    /// Rows from north to south of cells from west to east, the bot is in
+    /// the middle one
+    pub cells: Vec<Vec<MapCell>>,
+    pub markers: Vec<Marker>,
+}
+
+/// Starts sending maps of a bot, or stops with `None`
+pub fn handle_commands(mut events: EventReader<BotCommand>, mut commands: Commands) {
+    for BotCommand { entity, command, .. } in events.read() {
+        let CommandType::Minimap(request) = command else {
+            continue;
+        };
+        let Some(mut entity) = commands.get_entity(*entity) else {
+            continue;
+        };
+        match request {
+            Some(request) => {
+                entity.insert(MinimapState { request: *request, next_at: 0 });
+            }
+            None => {
+                entity.remove::<MinimapState>();
+            }
+        }
+    }
+}
+
+/// Sends a fresh map when one is due, called every tick
+pub fn poll(bot: &Client, name: &str, log: &LogChannel) {
+    let request = {
+        let mut ecs = bot.ecs.lock();
+        let Some(now) = ecs.get::<GameTicks>(bot.entity).map(|ticks| ticks.0) else {
+            return;
+        };
+        let Some(mut state) = ecs.get_mut::<MinimapState>(bot.entity) else {
+            return;
+        };
+        if now < state.next_at {
+            return;
+        }
+        state.next_at = now + INTERVAL_TICKS;
+        state.request
+    };
+    let map = build(bot, request);
+    log.send(ConsoleType::Minimap { bot: name.to_string(), map });
+}
+
+fn build(bot: &Client, request: MinimapRequest) -> Minimap {
+    let position = bot.position();
+    let center = BlockPos::from(position);
+    let radius = request.radius as i32;
+    let scale = request.scale.max(1) as i32;
+    let slice = request.slice.map(|offset| center.y + offset);
+
+    let cells = {
+        let world = bot.world();
+        let world = world.read();
+        (-radius..=radius)
+            .map(|dz| {
+                (-radius..=radius)
+                    .map(|dx| {
+                        let (x, z) = (center.x + dx * scale, center.z + dz * scale);
+                        match slice {
+                            Some(y) => slice_cell(&world, BlockPos::new(x, y, z), center.y),
+                            None => surface_cell(&world, x, z, center.y),
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+
+    let players = bot.tab_list();
+    let instance_name = bot.component::<InstanceName>();
+    let mut ecs = bot.ecs.lock();
+    let mut query = ecs.query_filtered::<(TargetData, Option<&EntityUuid>), TargetFilter>();
+    let markers = query
+        .iter(&ecs)
+        .filter(|((_, _, _, _, target_instance, _, _), _)| **target_instance == instance_name)
+        .filter_map(|((_, kind, target, _, _, monster, animal), uuid)| {
+            let (kind, label) = if **kind == EntityKind::Player {
+                let name = uuid.and_then(|uuid| players.get(&**uuid)).map(|info| info.profile.name.clone());
+                (MarkerKind::Player, name.unwrap_or_else(|| "player".to_string()))
+            } else if monster || animal {
+                let name = kind.to_string();
+                let kind = if monster { MarkerKind::Monster } else { MarkerKind::Animal };
+                (kind, name.trim_start_matches("minecraft:").to_string())
+            } else {
+                return None;
+            };
+            let cell = |offset: f64| {
+                let cell = (offset / f64::from(scale)).round() as i32 + radius;
+                usize::try_from(cell).ok().filter(|cell| *cell <= 2 * radius as usize)
+            };
+            Some(Marker { row: cell(target.z - position.z)?, col: cell(target.x - position.x)?, kind, label })
+        })
+        .collect();
+
+    Minimap { center, yaw: bot.direction().0, request, cells, markers }
+}
+
+fn surface_cell(world: &Instance, x: i32, z: i32, feet: i32) -> MapCell {
+    let pos = BlockPos::new(x, 0, z);
+    let Some(chunk) = world.chunks.get(&ChunkPos::from(&pos)) else {
+        return MapCell { terrain: Terrain::Unloaded, height: 0 };
+    };
+    let chunk = chunk.read();
+    let min_y = world.chunks.min_y;
+    let local = ChunkBlockPos::from(&pos);
+    let top = match chunk.heightmaps.get(&HeightmapKind::WorldSurface) {
+        Some(heightmap) => heightmap.get_highest_taken(local.x, local.z),
+        None => {
+            let max_y = min_y + world.chunks.height as i32 - 1;
+            (min_y..=max_y)
+                .rev()
+                .find(|y| chunk.get(&ChunkBlockPos::new(local.x, *y, local.z), min_y).is_some_and(|state| !state.is_air()))
+                .unwrap_or(min_y - 1)
+        }
+    };
+    if top < min_y {
+        return MapCell { terrain: Terrain::Empty, height: 0 };
+    }
+    let state = chunk.get(&ChunkBlockPos::new(local.x, top, local.z), min_y).unwrap_or_default();
+    MapCell { terrain: Terrain::of(state), height: top - feet }
+}
+
+fn slice_cell(world: &Instance, pos: BlockPos, feet: i32) -> MapCell {
+    let Some(state) = world.get_block_state(&pos) else {
+        return MapCell { terrain: Terrain::Unloaded, height: 0 };
+    };
+    let terrain = Terrain::of(state);
+    if terrain != Terrain::Empty {
+        return MapCell { terrain, height: pos.y - feet };
+    }
+    let floor = (1..=FLOOR_DEPTH).find(|depth| {
+        world.get_block_state(&pos.down(*depth)).is_some_and(|state| !state.is_air())
+    });
+    match floor {
+        Some(depth) => MapCell { terrain: Terrain::Floor, height: pos.y - depth - feet },
+        None => MapCell { terrain: Terrain::Empty, height: 0 },
+    }
+}
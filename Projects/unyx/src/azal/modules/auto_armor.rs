@@ -0,0 +1,330 @@
+use azalea::{
+    app::{App, Plugin},
+    core::{data_registry::ResolvableDataRegistry, registry_holder::RegistryHolder},
+    ecs::prelude::*,
+    entity::{metadata::Player, LocalEntity},
+    inventory::{
+        components::{Damage, Enchantments, MaxDamage, Unbreakable},
+        operations::PickupClick,
+        ContainerClickEvent,
+        Inventory,
+        InventorySet,
+        ItemStackData,
+        Player as PlayerMenu,
+    },
+    prelude::*,
+    registry::Item,
+    GameProfileComponent,
+    InstanceHolder,
+};
+
+use super::auto_eat::AutoEatState;
+use super::super::{bridge::BotLog, settings::module_settings, trackers::game_tick::*};
+
+/// Ticks between looking through the inventory
+const CHECK_INTERVAL_TICKS: u128 = 20;
+/// Ticks the server gets to confirm a swap before the next one
+const SETTLE_TICKS: u128 = 5;
+
+module_settings! {
+    /// Settings of the `auto_armor` module
+    pub struct AutoArmorSettings {
+        pub enabled: bool = true,
+        /// Pieces with less than this share of their durability left are taken
+        /// off before they break
+        pub min_durability: f64 = 0.05 => 0.0..=1.0,
+        /// Items held in the offhand, the first one in the list that is in the
+        /// inventory wins
+        pub offhand: Vec<String> = ["totem_of_undying", "shield"].map(String::from).to_vec(),
+    }
+}
+
+impl AutoArmorSettings {
+    /// Rank of `item` in the offhand list, higher is better
+    fn offhand_rank(&self, item: Item) -> Option<usize> {
+        let name = item_name(item);
+        let index = self.offhand.iter().position(|entry| *entry == name)?;
+        Some(self.offhand.len() - index)
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct AutoArmorState {
+    next_check: u128,
+}
+
+/// Where a piece of equipment goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EquipSlot {
+    Head,
+    Chest,
+    Legs,
+    Feet,
+    Offhand,
+}
+
+impl EquipSlot {
+    const ALL: [EquipSlot; 5] =
+        [EquipSlot::Head, EquipSlot::Chest, EquipSlot::Legs, EquipSlot::Feet, EquipSlot::Offhand];
+
+    /// Slot number in the player menu
+    fn menu_slot(self) -> usize {
+        let armor = *PlayerMenu::ARMOR_SLOTS.start();
+        match self {
+            EquipSlot::Head => armor,
+            EquipSlot::Chest => armor + 1,
+            EquipSlot::Legs => armor + 2,
+            EquipSlot::Feet => armor + 3,
+            EquipSlot::Offhand => PlayerMenu::OFFHAND_SLOT,
+        }
+    }
+
+    /// Durability of a piece made of a material with `multiplier`, like vanilla
+    fn durability(self, multiplier: i32) -> i32 {
+        let base = match self {
+            EquipSlot::Head => 11,
+            EquipSlot::Chest => 16,
+            EquipSlot::Legs => 15,
+            EquipSlot::Feet | EquipSlot::Offhand => 13,
+        };
+        base * multiplier
+    }
+}
+
+/// Slot, armor points, toughness and durability multiplier of every armor
+/// piece
+fn armor_stats(item: Item) -> Option<(EquipSlot, f64, f64, i32)> {
+    use EquipSlot::*;
+    Some(match item {
+        Item::NetheriteHelmet => (Head, 3.0, 3.0, 37),
+        Item::NetheriteChestplate => (Chest, 8.0, 3.0, 37),
+        Item::NetheriteLeggings => (Legs, 6.0, 3.0, 37),
+        Item::NetheriteBoots => (Feet, 3.0, 3.0, 37),
+        Item::DiamondHelmet => (Head, 3.0, 2.0, 33),
+        Item::DiamondChestplate => (Chest, 8.0, 2.0, 33),
+        Item::DiamondLeggings => (Legs, 6.0, 2.0, 33),
+        Item::DiamondBoots => (Feet, 3.0, 2.0, 33),
+        Item::TurtleHelmet => (Head, 2.0, 0.0, 25),
+        Item::IronHelmet => (Head, 2.0, 0.0, 15),
+        Item::IronChestplate => (Chest, 6.0, 0.0, 15),
+        Item::IronLeggings => (Legs, 5.0, 0.0, 15),
+        Item::IronBoots => (Feet, 2.0, 0.0, 15),
+        Item::ChainmailHelmet => (Head, 2.0, 0.0, 15),
+        Item::ChainmailChestplate => (Chest, 5.0, 0.0, 15),
+        Item::ChainmailLeggings => (Legs, 4.0, 0.0, 15),
+        Item::ChainmailBoots => (Feet, 1.0, 0.0, 15),
+        Item::GoldenHelmet => (Head, 2.0, 0.0, 7),
+        Item::GoldenChestplate => (Chest, 5.0, 0.0, 7),
+        Item::GoldenLeggings => (Legs, 3.0, 0.0, 7),
+        Item::GoldenBoots => (Feet, 1.0, 0.0, 7),
+        Item::LeatherHelmet => (Head, 1.0, 0.0, 5),
+        Item::LeatherChestplate => (Chest, 3.0, 0.0, 5),
+        Item::LeatherLeggings => (Legs, 2.0, 0.0, 5),
+        Item::LeatherBoots => (Feet, 1.0, 0.0, 5),
+        _ => return None,
+    })
+}
+
+/// Uses left and total uses, None for items that don't wear out
+fn durability(piece: &ItemStackData) -> Option<(i32, i32)> {
+    if piece.components.has::<Unbreakable>() {
+        return None;
+    }
+    let max = match piece.components.get::<MaxDamage>() {
+        Some(max) => max.amount,
+        None => match (piece.kind, armor_stats(piece.kind)) {
+            (_, Some((slot, _, _, multiplier))) => slot.durability(multiplier),
+            (Item::Shield, None) => 336,
+            _ => return None,
+        },
+    };
+    let damage = piece.components.get::<Damage>().map_or(0, |damage| damage.amount);
+    Some((max - damage, max))
+}
+
+/// Extra score from enchantments, None for a curse of binding since such a
+/// piece could never come off again
+fn enchantment_bonus(piece: &ItemStackData, registries: &RegistryHolder) -> Option<f64> {
+    let Some(enchantments) = piece.components.get::<Enchantments>() else {
+        return Some(0.0);
+    };
+    let mut bonus = 0.0;
+    for (enchantment, level) in &enchantments.levels {
+        let Some(name) = enchantment.resolve_name(registries) else {
+            continue;
+        };
+        let weight = match name.path.as_str() {
+            "binding_curse" => return None,
+            "protection" => 3.0,
+            "fire_protection" | "blast_protection" | "projectile_protection" => 1.5,
+            "mending" => 2.0,
+            "unbreaking" => 1.0,
+            "thorns" => 0.5,
+            _ => 0.0,
+        };
+        bonus += weight * f64::from(*level);
+    }
+    Some(bonus)
+}
+
+fn is_cursed(piece: &ItemStackData, registries: &RegistryHolder) -> bool {
+    enchantment_bonus(piece, registries).is_none()
+}
+
+/// How good `piece` is in `slot`, None when it does not go there, is cursed
+/// or about to break
+fn score(
+    piece: &ItemStackData,
+    slot: EquipSlot,
+    settings: &AutoArmorSettings,
+    registries: &RegistryHolder,
+) -> Option<f64> {
+    let base = match (slot, armor_stats(piece.kind)) {
+        (EquipSlot::Offhand, _) => settings.offhand_rank(piece.kind)? as f64 * 100.0,
+        (_, Some((piece_slot, armor, toughness, _))) if piece_slot == slot => armor * 10.0 + toughness * 4.0,
+        _ => return None,
+    };
+    let bonus = enchantment_bonus(piece, registries)?;
+    // among equal pieces the least worn one wins
+    let worn = match durability(piece) {
+        Some((left, max)) => {
+            let left = f64::from(left) / f64::from(max.max(1));
+            if left < settings.min_durability {
+                return None;
+            }
+            left
+        }
+        None => 1.0,
+    };
+    Some(base + bonus + worn)
+}
+
+fn item_name(item: Item) -> String {
+    item.to_string().trim_start_matches("minecraft:").to_string()
+}
+
+/// A move the module wants to make in the player menu
+enum Change {
+    /// Puts the piece from `from` on, whatever was worn goes back to `from`
+    Equip { slot: EquipSlot, from: usize },
+    /// Moves a worn out piece into the empty inventory slot `to`
+    TakeOff { slot: EquipSlot, to: usize },
+}
+
+/// The one change worth making right now, armor before the offhand
+fn next_change(
+    inventory: &Inventory,
+    settings: &AutoArmorSettings,
+    registries: &RegistryHolder,
+) -> Option<(Change, String)> {
+    let menu = &inventory.inventory_menu;
+    for slot in EquipSlot::ALL {
+        let worn = menu.slot(slot.menu_slot()).and_then(|item| item.as_present());
+        if worn.is_some_and(|piece| is_cursed(piece, registries)) {
+            continue;
+        }
+        let worn_score = worn.and_then(|piece| score(piece, slot, settings, registries));
+        let best = PlayerMenu::INVENTORY_SLOTS
+            .filter_map(|from| {
+                let piece = menu.slot(from)?.as_present()?;
+                Some((from, piece, score(piece, slot, settings, registries)?))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2));
+
+        match (worn, best) {
+            (_, Some((from, piece, best_score))) if worn_score.is_none_or(|worn_score| best_score > worn_score) => {
+                let line = match worn {
+                    Some(old) => format!("equipped {} instead of {}", item_name(piece.kind), item_name(old.kind)),
+                    None => format!("equipped {}", item_name(piece.kind)),
+                };
+                return Some((Change::Equip { slot, from }, line));
+            }
+            // nothing better to wear, but the piece on is about to break
+            (Some(old), _) if worn_score.is_none() && durability(old).is_some() && slot != EquipSlot::Offhand => {
+                let to = PlayerMenu::INVENTORY_SLOTS
+                    .into_iter()
+                    .find(|to| menu.slot(*to).is_some_and(|item| item.is_empty()))?;
+                let left = durability(old).map_or(0, |(left, _)| left);
+                let line = format!("took off {}, only {left} durability left", item_name(old.kind));
+                return Some((Change::TakeOff { slot, to }, line));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+pub struct AutoArmorPlugin;
+
+impl Plugin for AutoArmorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            GameTick,
+            Self::handle_auto_armor.after(GameTickPlugin::handle_game_ticks).before(InventorySet),
+        );
+    }
+}
+
+type QuerySet<'a> = (
+    Entity,
+    &'a GameTicks,
+    &'a Inventory,
+    &'a InstanceHolder,
+    &'a AutoArmorSettings,
+    &'a GameProfileComponent,
+    Option<&'a mut AutoArmorState>,
+    Option<&'a AutoEatState>,
+);
+
+type QueryFilter = (With<Player>, With<LocalEntity>);
+
+impl AutoArmorPlugin {
+    fn handle_auto_armor(
+        mut query: Query<QuerySet, QueryFilter>,
+        mut commands: Commands,
+        mut container_click_events: EventWriter<ContainerClickEvent>,
+        mut log_events: EventWriter<BotLog>,
+    ) {
+        for (entity, game_ticks, inventory, instance_holder, settings, profile, state, eating) in &mut query {
+            let now = game_ticks.0;
+            let Some(mut state) = state else {
+                commands.entity(entity).insert(AutoArmorState { next_check: now });
+                continue;
+            };
+            if !settings.enabled || now < state.next_check {
+                continue;
+            }
+            state.next_check = now + CHECK_INTERVAL_TICKS;
+            // armor slots are only reachable without a container open, and
+            // auto-eat may be moving food around right now
+            if inventory.id != 0 || !inventory.carried.is_empty() || AutoEatState::is_busy(eating) {
+                continue;
+            }
+
+            let change = {
+                let world = instance_holder.instance.read();
+                next_change(inventory, settings, &world.registries)
+            };
+            let Some((change, line)) = change else {
+                continue;
+            };
+            let (first, second) = match change {
+                Change::Equip { slot, from } => (from, slot.menu_slot()),
+                Change::TakeOff { slot, to } => (slot.menu_slot(), to),
+            };
+            let (Ok(first), Ok(second)) = (u16::try_from(first), u16::try_from(second)) else {
+                continue;
+            };
+            // pick up, swap with what is worn, put the old piece down
+            for slot in [first, second, first] {
+                container_click_events.send(ContainerClickEvent {
+                    entity,
+                    window_id: inventory.id,
+                    operation: PickupClick::Left { slot: Some(slot) }.into(),
+                });
+            }
+            log_events.send(BotLog::new(&profile.name, line));
+            state.next_check = now + SETTLE_TICKS;
+        }
+    }
+}
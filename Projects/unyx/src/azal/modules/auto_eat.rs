@@ -2,13 +2,15 @@ use std::{cmp::Ordering, collections::HashMap, sync::LazyLock};
 
 use azalea::{
     app::{App, Plugin},
+    core::direction::Direction,
     ecs::prelude::*,
-    entity::{metadata::Player, LocalEntity, LookDirection},
+    entity::{metadata::{Health, Player}, LocalEntity, LookDirection},
     inventory::{
         operations::{ClickOperation, SwapClick},
         ContainerClickEvent,
         Inventory,
         InventorySet,
+        SetSelectedHotbarSlotEvent,
     },
     mining::continue_mining_block,
     packet::game::{handle_outgoing_packets, SendPacketEvent},
@@ -16,14 +18,25 @@ use azalea::{
     prelude::*,
     protocol::packets::game::{
         s_interact::InteractionHand,
+        s_player_action::{Action, ServerboundPlayerAction},
         ServerboundGamePacket,
         ServerboundUseItem,
     },
     registry::Item,
+    BlockPos,
+    GameProfileComponent,
     Hunger,
 };
 
-use super::super::trackers::game_tick::*;
+use super::safety::SafetyState;
+use super::super::{bridge::BotLog, settings::module_settings, trackers::game_tick::*};
+
+/// Ticks between looking for food when nothing needs eating
+const CHECK_INTERVAL_TICKS: u128 = 20;
+/// Ticks the server gets to apply a hotbar change before we start eating
+const PREPARE_TICKS: u128 = 2;
+/// Extra ticks on top of the eating time, for lag
+const EAT_MARGIN_TICKS: u128 = 5;
 
 pub static FOOD_ITEMS: LazyLock<HashMap<Item, (i32, f32)>> = LazyLock::new(|| {
     HashMap::from([
@@ -35,6 +48,7 @@ pub static FOOD_ITEMS: LazyLock<HashMap<Item, (i32, f32)>> = LazyLock::new(|| {
         (Item::Bread, (5, 6.0)),
         (Item::Carrot, (3, 3.6)),
         (Item::Chicken, (2, 1.2)),
+        (Item::ChorusFruit, (4, 2.4)),
         (Item::Cod, (2, 0.4)),
         (Item::CookedBeef, (8, 12.8)),
         (Item::CookedChicken, (6, 7.2)),
@@ -53,17 +67,89 @@ pub static FOOD_ITEMS: LazyLock<HashMap<Item, (i32, f32)>> = LazyLock::new(|| {
         (Item::MelonSlice, (2, 1.2)),
         (Item::MushroomStew, (6, 7.2)),
         (Item::Mutton, (2, 1.2)),
+        (Item::PoisonousPotato, (2, 1.2)),
         (Item::Porkchop, (3, 1.8)),
         (Item::Potato, (1, 0.6)),
+        (Item::Pufferfish, (1, 0.2)),
         (Item::PumpkinPie, (8, 4.8)),
         (Item::Rabbit, (3, 1.8)),
         (Item::RabbitStew, (10, 12.0)),
+        (Item::RottenFlesh, (4, 0.8)),
         (Item::Salmon, (2, 0.4)),
+        (Item::SpiderEye, (2, 3.2)),
+        (Item::SuspiciousStew, (6, 7.2)),
         (Item::SweetBerries, (2, 0.4)),
         (Item::TropicalFish, (1, 0.2)),
     ])
 });
 
+module_settings! {
+    /// Settings of the `auto_eat` module
+    pub struct AutoEatSettings {
+        pub enabled: bool = true,
+        /// Eat once the food bar is at or below this
+        pub hunger_threshold: u32 = 17 => 0..=20,
+        /// Also eat while saturation is below this, as long as the food bar
+        /// is not full
+        pub min_saturation: f64 = 0.0 => 0.0..=20.0,
+        /// Foods only eaten when health is at or below `low_health`
+        pub avoid: Vec<String> = [
+            "chicken",
+            "rotten_flesh",
+            "spider_eye",
+            "poisonous_potato",
+            "pufferfish",
+            "chorus_fruit",
+            "suspicious_stew",
+            "golden_apple",
+            "enchanted_golden_apple",
+        ]
+        .map(String::from)
+        .to_vec(),
+        pub low_health: f64 = 8.0 => 0.0..=20.0,
+    }
+}
+
+impl AutoEatSettings {
+    fn wants_to_eat(&self, hunger: &Hunger) -> bool {
+        hunger.food < 20
+            && (hunger.food <= self.hunger_threshold || f64::from(hunger.saturation) < self.min_saturation)
+    }
+
+    fn allows(&self, item: Item, low_health: bool) -> bool {
+        if low_health {
+            return true;
+        }
+        let name = item.to_string();
+        let name = name.trim_start_matches("minecraft:");
+        !self.avoid.iter().any(|avoid| avoid == name)
+    }
+}
+
+/// What to put back once the bot is done eating
+#[derive(Clone, Copy, Debug)]
+pub struct Restore {
+    hotbar_slot: u8,
+    /// Inventory slot the food was swapped in from, swapped back afterwards
+    swapped_from: Option<u16>,
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub enum AutoEatState {
+    Idle { next_check: u128 },
+    /// Food is being moved into the hand
+    Preparing { restore: Restore, ready_at: u128 },
+    /// Holding right click until the food is eaten
+    Eating { restore: Restore, done_at: u128 },
+}
+
+impl AutoEatState {
+    /// Whether other modules should leave the hotbar alone
+    pub fn is_busy(state: Option<&Self>) -> bool {
+        matches!(state, Some(AutoEatState::Preparing { .. } | AutoEatState::Eating { .. }))
+    }
+}
+
 pub struct AutoEatPlugin;
 
 
@@ -85,63 +171,181 @@ type QuerySet<'a> = (
     Entity,
     &'a GameTicks,
     &'a Hunger,
+    &'a Health,
     &'a Inventory,
     &'a LookDirection,
+    &'a AutoEatSettings,
+    &'a GameProfileComponent,
+    Option<&'a mut AutoEatState>,
+    Option<&'a SafetyState>,
 );
 
 type QueryFilter = (With<Player>, With<LocalEntity>);
 
+/// Ticks it takes to eat `item`
+fn eating_ticks(item: Item) -> u128 {
+    match item {
+        Item::DriedKelp => 16,
+        Item::HoneyBottle => 40,
+        _ => 32,
+    }
+}
+
 impl AutoEatPlugin {
-    // Panic if slot > u16
     fn handle_auto_eat(
         mut query: Query<QuerySet, QueryFilter>,
+        mut commands: Commands,
         mut packet_events: EventWriter<SendPacketEvent>,
         mut container_click_events: EventWriter<ContainerClickEvent>,
+        mut hotbar_events: EventWriter<SetSelectedHotbarSlotEvent>,
+        mut log_events: EventWriter<BotLog>,
     ) {
-        for(entity, game_ticks, hunger, invetory, direction) in &mut query {
-                if hunger.food >= 18 {
-                    continue;
-                }
-                if !FOOD_ITEMS.contains_key(&invetory.held_item().kind()) {
-                    let mut food_slots = Vec::new();
-                    for slot in invetory.inventory_menu.player_slots_range() {
-                        let Some(item) = invetory.inventory_menu.slot(slot) else {
+        for (entity, game_ticks, hunger, health, inventory, direction, settings, profile, state, safety) in &mut query {
+            let now = game_ticks.0;
+            let Some(mut state) = state else {
+                commands.entity(entity).insert(AutoEatState::Idle { next_check: now });
+                continue;
+            };
+
+            match *state {
+                AutoEatState::Idle { next_check } => {
+                    // a hurt bot eats whenever it can, food is what heals it
+                    let hurt = SafetyState::is_hurt(safety);
+                    if !settings.enabled || now < next_check || !(settings.wants_to_eat(hunger) || hurt && hunger.food < 20) {
+                        continue;
+                    }
+                    // slot numbers only line up with the player menu when no container is open
+                    if inventory.id != 0 {
+                        continue;
+                    }
+                    let low_health = hurt || f64::from(**health) <= settings.low_health;
+                    let Some((slot, item)) = pick_food(inventory, settings, low_health) else {
+                        *state = AutoEatState::Idle { next_check: now + CHECK_INTERVAL_TICKS };
+                        continue;
+                    };
+
+                    let hotbar = inventory.inventory_menu.hotbar_slots_range();
+                    let mut restore = Restore { hotbar_slot: inventory.selected_hotbar_slot, swapped_from: None };
+                    if hotbar.contains(&slot) {
+                        let Ok(hotbar_slot) = u8::try_from(slot - *hotbar.start()) else {
                             continue;
                         };
-                        if let Some((nutrition, saturation)) = FOOD_ITEMS.get(&item.kind()) {
-                            food_slots.push((slot, *nutrition, *saturation));
+                        if hotbar_slot != inventory.selected_hotbar_slot {
+                            hotbar_events.send(SetSelectedHotbarSlotEvent { entity, slot: hotbar_slot });
                         }
-                    }
-                    food_slots.sort_by(|a, b| {
-                        b.2.partial_cmp(&a.2)
-                            .unwrap_or(Ordering::Equal)
-                            .then_with(|| b.1.cmp(&a.1))
-                    });
-    
-                    if let Some((slot, _, _)) = food_slots.first() {
+                    } else {
+                        let Ok(source_slot) = u16::try_from(slot) else {
+                            continue;
+                        };
                         container_click_events.send(ContainerClickEvent {
                             entity,
-                            window_id: invetory.id,
+                            window_id: inventory.id,
                             operation: ClickOperation::Swap(SwapClick {
-                                source_slot: u16::try_from(*slot).unwrap(),
-                                target_slot: invetory.selected_hotbar_slot,
+                                source_slot,
+                                target_slot: inventory.selected_hotbar_slot,
                             }),
                         });
+                        restore.swapped_from = Some(source_slot);
                     }
+                    let food = item.to_string();
+                    log_events.send(BotLog::new(&profile.name, format!("eating {}", food.trim_start_matches("minecraft:"))));
+                    *state = AutoEatState::Preparing { restore, ready_at: now + PREPARE_TICKS };
                 }
-    
-                let packet = ServerboundGamePacket::UseItem(ServerboundUseItem {
-                    hand:     InteractionHand::MainHand,
-                    pitch:    direction.x_rot,
-                    yaw:      direction.y_rot,
-                    sequence: 0,
-                });
-    
-                packet_events.send(SendPacketEvent {
-                    sent_by: entity,
-                    packet,
-                });
-                
+                AutoEatState::Preparing { restore, ready_at } => {
+                    if now < ready_at {
+                        continue;
+                    }
+                    let held = inventory.held_item().kind();
+                    if !FOOD_ITEMS.contains_key(&held) {
+                        // the swap did not go through, put things back and try again later
+                        Self::restore(entity, inventory, restore, &mut container_click_events, &mut hotbar_events);
+                        *state = AutoEatState::Idle { next_check: now + CHECK_INTERVAL_TICKS };
+                        continue;
+                    }
+                    packet_events.send(SendPacketEvent {
+                        sent_by: entity,
+                        packet: ServerboundGamePacket::UseItem(ServerboundUseItem {
+                            hand: InteractionHand::MainHand,
+                            pitch: direction.x_rot,
+                            yaw: direction.y_rot,
+                            sequence: 0,
+                        }),
+                    });
+                    *state = AutoEatState::Eating { restore, done_at: now + eating_ticks(held) + EAT_MARGIN_TICKS };
+                }
+                AutoEatState::Eating { restore, done_at } => {
+                    if now < done_at {
+                        continue;
+                    }
+                    // harmless when the server already finished the food
+                    packet_events.send(SendPacketEvent {
+                        sent_by: entity,
+                        packet: ServerboundGamePacket::PlayerAction(ServerboundPlayerAction {
+                            action: Action::ReleaseUseItem,
+                            pos: BlockPos::default(),
+                            direction: Direction::Down,
+                            sequence: 0,
+                        }),
+                    });
+                    Self::restore(entity, inventory, restore, &mut container_click_events, &mut hotbar_events);
+                    // give the server time to send the new food level
+                    *state = AutoEatState::Idle { next_check: now + CHECK_INTERVAL_TICKS };
+                }
+            }
+        }
+    }
+
+    fn restore(
+        entity: Entity,
+        inventory: &Inventory,
+        restore: Restore,
+        container_click_events: &mut EventWriter<ContainerClickEvent>,
+        hotbar_events: &mut EventWriter<SetSelectedHotbarSlotEvent>,
+    ) {
+        if let Some(source_slot) = restore.swapped_from
+            && inventory.id == 0
+        {
+            container_click_events.send(ContainerClickEvent {
+                entity,
+                window_id: inventory.id,
+                operation: ClickOperation::Swap(SwapClick {
+                    source_slot,
+                    target_slot: inventory.selected_hotbar_slot,
+                }),
+            });
+        }
+        if restore.hotbar_slot != inventory.selected_hotbar_slot {
+            hotbar_events.send(SetSelectedHotbarSlotEvent { entity, slot: restore.hotbar_slot });
+        }
+    }
+}
+
+/// Best food the policy allows by saturation and nutrition, hotbar slots win
+/// ties so we swap as little as possible
+fn pick_food(inventory: &Inventory, settings: &AutoEatSettings, low_health: bool) -> Option<(usize, Item)> {
+    let menu = &inventory.inventory_menu;
+    let hotbar = menu.hotbar_slots_range();
+    let mut food_slots = Vec::new();
+    for slot in hotbar.clone().chain(menu.player_slots_range()) {
+        let Some(item) = menu.slot(slot) else {
+            continue;
+        };
+        let kind = item.kind();
+        if !settings.allows(kind, low_health) {
+            continue;
+        }
+        if let Some((nutrition, saturation)) = FOOD_ITEMS.get(&kind) {
+            food_slots.push((slot, kind, *nutrition, *saturation));
         }
     }
+    food_slots.sort_by(|a, b| {
+        let golden = |item: Item| matches!(item, Item::GoldenApple | Item::EnchantedGoldenApple);
+        // golden apples are what the policy is saving up for when health is low
+        (low_health && golden(b.1))
+            .cmp(&(low_health && golden(a.1)))
+            .then_with(|| b.3.partial_cmp(&a.3).unwrap_or(Ordering::Equal))
+            .then_with(|| b.2.cmp(&a.2))
+            .then_with(|| hotbar.contains(&b.0).cmp(&hotbar.contains(&a.0)))
+    });
+    food_slots.first().map(|(slot, kind, _, _)| (*slot, *kind))
 }
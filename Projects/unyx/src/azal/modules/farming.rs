@@ -0,0 +1,85 @@
+// Crops the `farm` task knows about and its settings. The task itself lives
+// with the other tasks, this only tells it what is ripe, what it is planted
+// with and how often to come back.
+
+use azalea::{
+    blocks::{properties, BlockState},
+    prelude::*,
+    registry::{Block, Item},
+};
+
+use super::super::settings::module_settings;
+
+module_settings! {
+    /// Settings of the `farm` task
+    pub struct FarmingSettings {
+        /// Game ticks between two passes over the farm
+        pub interval: u32 = 1200 => 20..=72_000,
+        /// Grow young crops with bone meal from the inventory
+        pub bonemeal: bool = false,
+        /// Put the harvest in the storage chests after every pass
+        pub deposit: bool = true,
+        /// Seeds of each kind kept back from a deposit for replanting
+        pub keep_seeds: u32 = 64 => 0..=2304,
+    }
+}
+
+/// A crop and what it takes to replant it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Crop {
+    Wheat,
+    Carrots,
+    Potatoes,
+    Beetroots,
+    NetherWart,
+}
+
+impl Crop {
+    pub const ALL: [Crop; 5] = [Crop::Wheat, Crop::Carrots, Crop::Potatoes, Crop::Beetroots, Crop::NetherWart];
+
+    pub fn of(state: BlockState) -> Option<Self> {
+        match Block::from(state) {
+            Block::Wheat => Some(Crop::Wheat),
+            Block::Carrots => Some(Crop::Carrots),
+            Block::Potatoes => Some(Crop::Potatoes),
+            Block::Beetroots => Some(Crop::Beetroots),
+            Block::NetherWart => Some(Crop::NetherWart),
+            _ => None,
+        }
+    }
+
+    /// Whether `state` is this crop at its last age
+    pub fn is_mature(self, state: BlockState) -> bool {
+        match self {
+            Crop::Wheat => state.property::<properties::WheatAge>() == Some(properties::WheatAge::_7),
+            Crop::Carrots => state.property::<properties::CarrotsAge>() == Some(properties::CarrotsAge::_7),
+            Crop::Potatoes => state.property::<properties::PotatoesAge>() == Some(properties::PotatoesAge::_7),
+            Crop::Beetroots => state.property::<properties::BeetrootsAge>() == Some(properties::BeetrootsAge::_3),
+            Crop::NetherWart => state.property::<properties::NetherWartAge>() == Some(properties::NetherWartAge::_3),
+        }
+    }
+
+    /// What gets planted to grow it again
+    pub fn seed(self) -> Item {
+        match self {
+            Crop::Wheat => Item::WheatSeeds,
+            Crop::Carrots => Item::Carrot,
+            Crop::Potatoes => Item::Potato,
+            Crop::Beetroots => Item::BeetrootSeeds,
+            Crop::NetherWart => Item::NetherWart,
+        }
+    }
+
+    /// The block it grows on
+    pub fn soil(self) -> Block {
+        match self {
+            Crop::NetherWart => Block::SoulSand,
+            _ => Block::Farmland,
+        }
+    }
+
+    /// Bone meal does nothing to nether wart
+    pub fn takes_bonemeal(self) -> bool {
+        self != Crop::NetherWart
+    }
+}
@@ -0,0 +1,317 @@
+use std::{sync::Arc, time::Duration};
+
+use azalea::{
+    app::{App, Plugin},
+    attack::{AttackEvent, AttackStrengthScale},
+    ecs::{prelude::*, query::QueryItem},
+    entity::{
+        metadata::{AbstractAnimal, AbstractMonster, Player},
+        Dead, EntityKind, EyeHeight, LocalEntity, Position,
+    },
+    inventory::{Inventory, SetSelectedHotbarSlotEvent},
+    pathfinder::{astar::PathfinderTimeout, goals::RadiusGoal, moves, GotoEvent},
+    physics::clip::{clip, BlockShapeType, ClipContext, FluidPickType},
+    prelude::*,
+    registry::Item,
+    world::{Instance, InstanceName, MinecraftEntityId},
+    GameProfileComponent, InstanceHolder, LookAtEvent, Vec3,
+};
+
+use super::{auto_eat::AutoEatState, safety::SafetyState};
+use super::super::{
+    bridge::BotLog,
+    settings::module_settings,
+    tasks::{follow::Escort, Priority, TaskKind, TaskQueue},
+    trackers::game_tick::*,
+};
+
+/// How often a chase re-paths towards its moving target
+const CHASE_REPATH_TICKS: u128 = 10;
+
+module_settings! {
+    /// Settings of the `killaura` module
+    pub struct KillauraSettings {
+        pub enabled: bool = true,
+        /// Targets closer than this get hit
+        pub attack_range: f64 = 4.0 => 0.0..=6.0,
+        /// Targets closer than this get chased, 0 to never move
+        pub chase_range: f64 = 10.0 => 0.0..=64.0,
+        /// Entity names like `zombie`, or `monster`, `animal` for whole groups
+        pub allow: Vec<String> = vec!["monster".into()],
+        /// Never attacked even when allowed, creepers blow up in melee
+        pub deny: Vec<String> = vec!["creeper".into()],
+        /// Skip targets behind blocks
+        pub line_of_sight: bool = true,
+        /// Switch to the best weapon in the hotbar before hitting
+        pub select_weapon: bool = true,
+        /// While following a player, only targets this close to them get
+        /// fought so the bot stays at their side
+        pub escort_range: f64 = 8.0 => 0.0..=64.0,
+    }
+}
+
+impl KillauraSettings {
+    fn matches(list: &[String], kind: &str, monster: bool, animal: bool) -> bool {
+        list.iter().any(|entry| {
+            entry == kind || (entry == "monster" && monster) || (entry == "animal" && animal)
+        })
+    }
+
+    fn is_target(&self, kind: EntityKind, monster: bool, animal: bool) -> bool {
+        let kind = kind.to_string();
+        let kind = kind.trim_start_matches("minecraft:");
+        Self::matches(&self.allow, kind, monster, animal) && !Self::matches(&self.deny, kind, monster, animal)
+    }
+}
+
+pub struct KillauraPlugin;
+
+impl Plugin for KillauraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            GameTick,
+            Self::handle_killaura.after(GameTickPlugin::handle_game_ticks),
+        );
+    }
+}
+
+/// An entity killaura picked, with its distance from the bot's eyes
+pub struct KillauraTarget {
+    pub id: MinecraftEntityId,
+    pub position: Vec3,
+    pub distance: f64,
+}
+
+/// What killaura knows about a possible target, also what the minimap marks
+pub type TargetData = (
+    &'static MinecraftEntityId,
+    &'static EntityKind,
+    &'static Position,
+    &'static EyeHeight,
+    &'static InstanceName,
+    Has<AbstractMonster>,
+    Has<AbstractAnimal>,
+);
+
+pub type TargetFilter = (Without<LocalEntity>, Without<Dead>);
+
+type QuerySet<'a> = (
+    Entity,
+    &'a KillauraSettings,
+    &'a GameTicks,
+    &'a Position,
+    &'a EyeHeight,
+    &'a InstanceName,
+    &'a InstanceHolder,
+    &'a AttackStrengthScale,
+    &'a Inventory,
+    &'a GameProfileComponent,
+    Option<&'a mut TaskQueue>,
+    Option<&'a AutoEatState>,
+    Option<&'a Escort>,
+    Option<&'a SafetyState>,
+);
+
+type QueryFilter = (With<Player>, With<LocalEntity>);
+
+impl KillauraPlugin {
+    #[allow(clippy::too_many_arguments)]
+    fn handle_killaura(
+        mut query: Query<QuerySet, QueryFilter>,
+        targets: Query<TargetData, TargetFilter>,
+        mut attack_events: EventWriter<AttackEvent>,
+        mut look_events: EventWriter<LookAtEvent>,
+        mut hotbar_events: EventWriter<SetSelectedHotbarSlotEvent>,
+        mut goto_events: EventWriter<GotoEvent>,
+        mut log_events: EventWriter<BotLog>,
+    ) {
+        for (
+            entity,
+            settings,
+            game_ticks,
+            position,
+            eye_height,
+            instance_name,
+            instance_holder,
+            attack_strength,
+            inventory,
+            profile,
+            tasks,
+            eating,
+            escort,
+            safety,
+        ) in &mut query
+        {
+            // hitting or switching items would cancel the food, and a bot in
+            // danger runs instead of fighting
+            if !settings.enabled || AutoEatState::is_busy(eating) || SafetyState::is_retreating(safety) {
+                continue;
+            }
+            let eye_position = position.up(eye_height.into());
+            let guarded = guarded_area(settings, escort, tasks.as_deref());
+            let candidates = candidates(settings, eye_position, instance_name, guarded, targets.iter());
+            let target = nearest_target(settings, eye_position, &instance_holder.instance.read(), candidates);
+            let Some(target) = target else {
+                continue;
+            };
+
+            if target.distance < settings.attack_range {
+                // switching items resets the cooldown, so do it before waiting on it
+                if settings.select_weapon
+                    && let Some(slot) = best_weapon_slot(inventory)
+                    && slot != inventory.selected_hotbar_slot
+                {
+                    hotbar_events.send(SetSelectedHotbarSlotEvent { entity, slot });
+                    continue;
+                }
+                if **attack_strength < 1.0 {
+                    continue;
+                }
+                look_events.send(LookAtEvent { entity, position: target.position });
+                attack_events.send(AttackEvent { entity, target: target.id });
+                continue;
+            }
+
+            // out of reach: walk over when idle, or let the task queue make
+            // room for a fight when the bot is busy
+            let busy = tasks.as_ref().is_some_and(|tasks| tasks.current().is_some());
+            if !busy {
+                if game_ticks.0 % CHASE_REPATH_TICKS == 0 {
+                    goto_events.send(GotoEvent {
+                        entity,
+                        goal: Arc::new(RadiusGoal {
+                            pos: target.position,
+                            radius: (settings.attack_range - 1.0).max(1.0) as f32,
+                        }),
+                        successors_fn: moves::default_move,
+                        allow_mining: false,
+                        min_timeout: PathfinderTimeout::Time(Duration::from_secs(1)),
+                        max_timeout: PathfinderTimeout::Time(Duration::from_secs(5)),
+                    });
+                }
+            } else if let Some(mut tasks) = tasks
+                && !tasks.contains(|kind| matches!(kind, TaskKind::Defend))
+            {
+                // the handler starts the runner on its next tick
+                let id = tasks.push(TaskKind::Defend, Priority::High);
+                log_events.send(BotLog::new(&profile.name, format!("queued task #{id}: defend")));
+            }
+        }
+    }
+}
+
+/// Around which point and how far targets are fought while the bot escorts a
+/// player, the escort is stale once its follow task is gone
+fn guarded_area(
+    settings: &KillauraSettings,
+    escort: Option<&Escort>,
+    tasks: Option<&TaskQueue>,
+) -> Option<(Vec3, f64)> {
+    let escort = escort?;
+    let following = tasks?.contains(|kind| matches!(kind, TaskKind::Follow(job) if job.player == escort.player));
+    following.then_some((escort.position, settings.escort_range))
+}
+
+/// Entities `settings` allows within chase range, or attack range when
+/// chasing is off, and within the guarded area when there is one, nearest
+/// first
+fn candidates<'a>(
+    settings: &KillauraSettings,
+    eye_position: Vec3,
+    instance_name: &InstanceName,
+    guarded: Option<(Vec3, f64)>,
+    targets: impl Iterator<Item = QueryItem<'a, TargetData>>,
+) -> Vec<KillauraTarget> {
+    let range = settings.chase_range.max(settings.attack_range);
+    let mut candidates = targets
+        .filter(|(_, _, _, _, target_instance, _, _)| *target_instance == instance_name)
+        .filter(|(_, kind, _, _, _, monster, animal)| settings.is_target(**kind, *monster, *animal))
+        .map(|(id, _, position, eye_height, _, _, _)| KillauraTarget {
+            id: *id,
+            position: position.up(eye_height.into()),
+            distance: eye_position.distance_to(position),
+        })
+        .filter(|target| target.distance < range)
+        .filter(|target| guarded.is_none_or(|(center, radius)| center.distance_to(&target.position) < radius))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    candidates
+}
+
+/// Nearest of `candidates` that can be seen, if `settings` asks for that
+fn nearest_target(
+    settings: &KillauraSettings,
+    eye_position: Vec3,
+    world: &Instance,
+    candidates: Vec<KillauraTarget>,
+) -> Option<KillauraTarget> {
+    candidates
+        .into_iter()
+        .find(|target| !settings.line_of_sight || has_line_of_sight(world, eye_position, target.position))
+}
+
+/// Target killaura would go for right now, for tasks that work with it
+pub fn current_target(bot: &Client) -> Option<(KillauraTarget, f64)> {
+    let settings = bot.get_component::<KillauraSettings>()?;
+    let eye_position = bot.eye_position();
+    let instance_name = bot.get_component::<InstanceName>()?;
+    // the schedule write-locks the world while it holds the ECS, so the ECS
+    // has to be let go before the world is read
+    let candidates = {
+        let mut ecs = bot.ecs.lock();
+        let guarded = guarded_area(&settings, ecs.get::<Escort>(bot.entity), ecs.get::<TaskQueue>(bot.entity));
+        let mut query = ecs.query_filtered::<TargetData, TargetFilter>();
+        candidates(&settings, eye_position, &instance_name, guarded, query.iter(&ecs))
+    };
+    let world = bot.world();
+    let target = nearest_target(&settings, eye_position, &world.read(), candidates)?;
+    Some((target, settings.attack_range))
+}
+
+fn has_line_of_sight(world: &Instance, from: Vec3, to: Vec3) -> bool {
+    let hit = clip(
+        &world.chunks,
+        ClipContext {
+            from,
+            to,
+            block_shape_type: BlockShapeType::Collider,
+            fluid_pick_type: FluidPickType::None,
+        },
+    );
+    hit.miss
+}
+
+/// Hotbar slot of the weapon with the best damage per second, if any
+fn best_weapon_slot(inventory: &Inventory) -> Option<u8> {
+    let menu = &inventory.inventory_menu;
+    let hotbar = menu.hotbar_slots_range();
+    let start = *hotbar.start();
+    hotbar
+        .filter_map(|slot| {
+            let dps = weapon_dps(menu.slot(slot)?.kind())?;
+            Some((u8::try_from(slot - start).ok()?, dps))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(slot, _)| slot)
+}
+
+/// Attack damage times attack speed of melee weapons
+fn weapon_dps(item: Item) -> Option<f32> {
+    let (damage, speed) = match item {
+        Item::NetheriteSword => (8.0, 1.6),
+        Item::DiamondSword => (7.0, 1.6),
+        Item::IronSword => (6.0, 1.6),
+        Item::StoneSword => (5.0, 1.6),
+        Item::GoldenSword | Item::WoodenSword => (4.0, 1.6),
+        Item::NetheriteAxe => (10.0, 1.0),
+        Item::DiamondAxe => (9.0, 1.0),
+        Item::IronAxe => (9.0, 0.9),
+        Item::StoneAxe => (9.0, 0.8),
+        Item::GoldenAxe => (7.0, 1.0),
+        Item::WoodenAxe => (7.0, 0.8),
+        Item::Trident => (9.0, 1.1),
+        Item::Mace => (6.0, 0.6),
+        _ => return None,
+    };
+    Some(damage * speed)
+}
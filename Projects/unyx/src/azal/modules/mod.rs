@@ -1,7 +1,14 @@
+pub mod auto_armor;
 pub mod auto_eat;
+pub mod farming;
+pub mod killaura;
+pub mod safety;
 
 use azalea::app::{PluginGroup, PluginGroupBuilder};
+use auto_armor::AutoArmorPlugin;
 use auto_eat::AutoEatPlugin;
+use killaura::KillauraPlugin;
+use safety::SafetyPlugin;
 
 
 pub struct ModulesPluginGroup;
@@ -10,5 +17,8 @@ impl PluginGroup for ModulesPluginGroup {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
             .add(AutoEatPlugin)
+            .add(AutoArmorPlugin)
+            .add(KillauraPlugin)
+            .add(SafetyPlugin)
     }
 }
\ No newline at end of file
@@ -0,0 +1,219 @@
+// Keeps the bot alive. Every tick it looks at health, falls, fire, lava, air
+// and explosives nearby. Any danger queues a high priority `retreat` task that
+// puts the other tasks on hold and keeps killaura out of fights, auto-eat eats
+// to heal, and health at the last threshold makes the bot leave the server.
+
+use std::fmt;
+
+use azalea::{
+    app::{App, Plugin},
+    disconnect::DisconnectEvent,
+    ecs::prelude::*,
+    entity::{
+        metadata::{AirSupply, Health, OnFire, Player},
+        Dead, EntityKind, LocalEntity, Physics, Position,
+    },
+    prelude::*,
+    registry,
+    world::InstanceName,
+    GameProfileComponent, Vec3,
+};
+
+use super::super::{
+    bridge::BotLog,
+    reconnect::SwarmState,
+    settings::module_settings,
+    tasks::{Priority, TaskKind, TaskQueue},
+    trackers::game_tick::*,
+};
+
+/// Health above `retreat_health` the bot needs before it goes back to work
+const RECOVER_MARGIN: f64 = 4.0;
+
+module_settings! {
+    /// Settings of the `safety` module
+    pub struct SafetySettings {
+        pub enabled: bool = true,
+        /// At or below this health the bot stops fighting and retreats
+        pub retreat_health: f64 = 10.0 => 0.0..=20.0,
+        /// At or below this health the bot leaves the server and stays off,
+        /// 0 to never
+        pub disconnect_health: f64 = 4.0 => 0.0..=20.0,
+        /// Falls longer than this put the tasks on hold until the bot landed
+        pub max_fall: f64 = 6.0 => 0.0..=384.0,
+        /// Get out of the water once the air bar (0-300) drops to this
+        pub min_air: u32 = 120 => 0..=300,
+        /// Creepers and lit TNT closer than this are run away from
+        pub explosive_range: f64 = 7.0 => 0.0..=32.0,
+        /// Waypoint to retreat to at low health, none to just back off
+        pub home: String = "home".into(),
+    }
+}
+
+/// What the bot is in danger of, the first one that applies wins
+#[derive(Clone, Copy, Debug)]
+pub enum Danger {
+    Lava,
+    /// Creeper or lit TNT at this position
+    Explosive(Vec3),
+    Fire,
+    Drowning,
+    Falling,
+    LowHealth,
+}
+
+impl fmt::Display for Danger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Danger::Lava => write!(f, "in lava"),
+            Danger::Explosive(_) => write!(f, "explosive nearby"),
+            Danger::Fire => write!(f, "on fire"),
+            Danger::Drowning => write!(f, "running out of air"),
+            Danger::Falling => write!(f, "falling"),
+            Danger::LowHealth => write!(f, "low health"),
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug, Default)]
+pub struct SafetyState {
+    pub danger: Option<Danger>,
+    /// Height the bot last stood at, azalea does not track falls itself
+    ground_y: Option<f64>,
+    /// Low health, kept until the bot healed a bit past the threshold
+    hurt: bool,
+    /// Set once the bot left the server to stay alive
+    disconnected: bool,
+}
+
+impl SafetyState {
+    /// Whether killaura should stay out of fights
+    pub fn is_retreating(state: Option<&Self>) -> bool {
+        state.is_some_and(|state| state.danger.is_some())
+    }
+
+    /// Whether auto-eat should eat to heal
+    pub fn is_hurt(state: Option<&Self>) -> bool {
+        state.is_some_and(|state| state.hurt)
+    }
+}
+
+fn is_explosive(kind: registry::EntityKind) -> bool {
+    matches!(kind, registry::EntityKind::Creeper | registry::EntityKind::Tnt)
+}
+
+pub struct SafetyPlugin;
+
+impl Plugin for SafetyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(GameTick, Self::handle_safety.after(GameTickPlugin::handle_game_ticks));
+    }
+}
+
+type QuerySet<'a> = (
+    Entity,
+    &'a SafetySettings,
+    &'a Health,
+    &'a Physics,
+    &'a Position,
+    &'a OnFire,
+    &'a AirSupply,
+    &'a InstanceName,
+    &'a GameProfileComponent,
+    Option<&'a mut SafetyState>,
+    Option<&'a mut TaskQueue>,
+);
+
+type QueryFilter = (With<Player>, With<LocalEntity>);
+
+type ExplosiveData = (&'static EntityKind, &'static Position, &'static InstanceName);
+
+impl SafetyPlugin {
+    #[allow(clippy::too_many_arguments)]
+    fn handle_safety(
+        mut query: Query<QuerySet, QueryFilter>,
+        explosives: Query<ExplosiveData, (Without<LocalEntity>, Without<Dead>)>,
+        swarm_state: Res<SwarmState>,
+        mut commands: Commands,
+        mut disconnect_events: EventWriter<DisconnectEvent>,
+        mut log_events: EventWriter<BotLog>,
+    ) {
+        for (entity, settings, health, physics, position, on_fire, air, instance_name, profile, state, tasks) in
+            &mut query
+        {
+            let Some(mut state) = state else {
+                commands.entity(entity).insert(SafetyState::default());
+                continue;
+            };
+            let health = f64::from(**health);
+            // dead bots respawn on their own
+            if !settings.enabled || state.disconnected || health <= 0.0 {
+                state.danger = None;
+                continue;
+            }
+
+            if health <= settings.disconnect_health {
+                let reason = format!("health dropped to {health:.1}, left the server to stay alive");
+                log_events.send(BotLog::new(&profile.name, format!("disconnecting: {reason}")));
+                swarm_state.stay_offline(&profile.name, reason.clone());
+                disconnect_events.send(DisconnectEvent { entity, reason: Some(reason.into()) });
+                state.disconnected = true;
+                continue;
+            }
+
+            if physics.on_ground() || physics.is_in_water() {
+                state.ground_y = Some(position.y);
+            }
+            let fall = state.ground_y.map_or(0.0, |y| y - position.y);
+            state.hurt = if state.hurt {
+                health < settings.retreat_health + RECOVER_MARGIN
+            } else {
+                health <= settings.retreat_health
+            };
+            let explosive = explosives
+                .iter()
+                .filter(|(kind, _, instance)| *instance == instance_name && is_explosive(***kind))
+                .map(|(_, explosive, _)| **explosive)
+                .filter(|explosive| explosive.distance_to(position) < settings.explosive_range)
+                .min_by(|a, b| a.distance_to(position).total_cmp(&b.distance_to(position)));
+
+            let danger = if physics.is_in_lava() {
+                Some(Danger::Lava)
+            } else if let Some(explosive) = explosive {
+                Some(Danger::Explosive(explosive))
+            } else if **on_fire {
+                Some(Danger::Fire)
+            } else if i64::from(**air) <= i64::from(settings.min_air) {
+                Some(Danger::Drowning)
+            } else if !physics.on_ground() && physics.velocity.y < 0.0 && fall > settings.max_fall {
+                Some(Danger::Falling)
+            } else if state.hurt {
+                Some(Danger::LowHealth)
+            } else {
+                None
+            };
+
+            let before = state.danger.map(|danger| danger.to_string());
+            let after = danger.map(|danger| danger.to_string());
+            if before != after {
+                let line = match &after {
+                    Some(danger) => format!("danger: {danger}, health {health:.1}"),
+                    None => "safe again".to_string(),
+                };
+                log_events.send(BotLog::new(&profile.name, line));
+            }
+            state.danger = danger;
+
+            let (Some(_), Some(mut tasks)) = (danger, tasks) else {
+                continue;
+            };
+            // a fight is what got the bot here, retreating wins over it
+            tasks.tasks.retain(|task| !matches!(task.kind, TaskKind::Defend));
+            if !tasks.contains(|kind| matches!(kind, TaskKind::Retreat)) {
+                // the handler starts the runner on its next tick
+                let id = tasks.push(TaskKind::Retreat, Priority::High);
+                log_events.send(BotLog::new(&profile.name, format!("queued task #{id}: retreat")));
+            }
+        }
+    }
+}
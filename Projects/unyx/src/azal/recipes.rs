@@ -0,0 +1,376 @@
+// Crafting recipes read from a vanilla recipe export, the
+// `data/minecraft/recipe` directory of the server jar or a data pack. Only
+// crafting table recipes are kept, furnaces and special recipes like
+// firework stars are skipped.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use azalea::{registry::Item, BlockPos};
+use serde::Deserialize;
+
+use super::{
+    block_names::{registry_names, suggest},
+    item_tags::ITEM_TAGS,
+};
+use crate::config::{CraftingConfig, SpotConfig};
+
+/// Recipes by the item they make, in the order they were read
+static RECIPES: OnceLock<HashMap<Item, Vec<Recipe>>> = OnceLock::new();
+/// Crafting tables from the config, `craft` uses the first one
+static TABLES: OnceLock<Vec<BlockPos>> = OnceLock::new();
+
+/// What fits one slot of a recipe
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ingredient {
+    /// `stick`, `#planks` or `coal/charcoal`, for messages
+    pub name: String,
+    pub items: Vec<Item>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Recipe {
+    pub id: String,
+    pub result: Item,
+    pub count: i32,
+    /// Columns of the pattern, 0 for shapeless recipes
+    width: usize,
+    /// Row by row, `None` for a slot that stays empty
+    slots: Vec<Option<Ingredient>>,
+}
+
+impl Recipe {
+    /// Whether it fits the 2x2 grid of the player's inventory
+    pub fn fits_inventory(&self) -> bool {
+        match self.width {
+            0 => self.slots.len() <= 4,
+            width => width <= 2 && self.slots.len() / width <= 2,
+        }
+    }
+
+    /// Grid slot and ingredient of every slot that isn't empty, for a grid
+    /// `grid_width` wide whose slots start at 1 like in the menus
+    pub fn placement(&self, grid_width: usize) -> Vec<(usize, &Ingredient)> {
+        let width = if self.width == 0 { grid_width } else { self.width };
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| Some((1 + i / width * grid_width + i % width, slot.as_ref()?)))
+            .collect()
+    }
+
+    /// Every distinct ingredient and how many one craft takes of it
+    pub fn ingredients(&self) -> Vec<(&Ingredient, i32)> {
+        let mut ingredients: Vec<(&Ingredient, i32)> = Vec::new();
+        for ingredient in self.slots.iter().flatten() {
+            match ingredients.iter_mut().find(|(seen, _)| *seen == ingredient) {
+                Some((_, uses)) => *uses += 1,
+                None => ingredients.push((ingredient, 1)),
+            }
+        }
+        ingredients
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum RecipeJson {
+    #[serde(rename = "minecraft:crafting_shaped")]
+    Shaped { key: HashMap<String, IngredientJson>, pattern: Vec<String>, result: ResultJson },
+    #[serde(rename = "minecraft:crafting_shapeless")]
+    Shapeless { ingredients: Vec<IngredientJson>, result: ResultJson },
+    #[serde(other)]
+    Other,
+}
+
+/// Exports since 1.21.2 use plain strings, older ones the objects
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IngredientJson {
+    Name(String),
+    Item { item: String },
+    Tag { tag: String },
+    AnyOf(Vec<IngredientJson>),
+}
+
+#[derive(Deserialize)]
+struct ResultJson {
+    #[serde(alias = "item")]
+    id: String,
+    #[serde(default = "one")]
+    count: i32,
+}
+
+fn one() -> i32 {
+    1
+}
+
+/// Reads the recipes and stores the crafting tables of `[crafting]`, a
+/// missing recipe directory leaves `craft` without recipes
+pub fn load(config: &CraftingConfig) -> Result<(), String> {
+    let mut files = Vec::new();
+    if config.recipes.is_dir() {
+        json_files(&config.recipes, &mut files)?;
+    }
+    let mut recipes: HashMap<Item, Vec<Recipe>> = HashMap::new();
+    files.sort();
+    for path in files {
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("could not read {}: {e}", path.display()))?;
+        let json = serde_json::from_str::<RecipeJson>(&text)
+            .map_err(|e| format!("invalid recipe {}: {e}", path.display()))?;
+        let id = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        // recipes naming items or tags of another version are left out
+        if let Some(recipe) = recipe(id, json) {
+            recipes.entry(recipe.result).or_default().push(recipe);
+        }
+    }
+    RECIPES.set(recipes).map_err(|_| "recipes were already loaded".to_string())?;
+    let tables = config.tables.iter().filter_map(SpotConfig::resolve).collect();
+    TABLES.set(tables).map_err(|_| "crafting tables were already loaded".to_string())
+}
+
+fn json_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("could not read {}: {e}", dir.display()))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("could not read {}: {e}", dir.display()))?.path();
+        if path.is_dir() {
+            json_files(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "json") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn recipe(id: String, json: RecipeJson) -> Option<Recipe> {
+    let (width, slots, result) = match json {
+        RecipeJson::Shaped { key, pattern, result } => {
+            let width = pattern.iter().map(|row| row.chars().count()).max()?;
+            let mut slots = Vec::new();
+            for row in &pattern {
+                let mut symbols = row.chars();
+                for _ in 0..width {
+                    slots.push(match symbols.next() {
+                        None | Some(' ') => None,
+                        Some(symbol) => Some(ingredient(key.get(&symbol.to_string())?)?),
+                    });
+                }
+            }
+            (width, slots, result)
+        }
+        RecipeJson::Shapeless { ingredients, result } => {
+            let slots = ingredients.iter().map(|json| ingredient(json).map(Some)).collect::<Option<Vec<_>>>()?;
+            (0, slots, result)
+        }
+        RecipeJson::Other => return None,
+    };
+    if slots.len() > 9 {
+        return None;
+    }
+    Some(Recipe { id, result: item(&result.id)?, count: result.count, width, slots })
+}
+
+fn ingredient(json: &IngredientJson) -> Option<Ingredient> {
+    match json {
+        IngredientJson::Name(name) => match name.strip_prefix('#') {
+            Some(tag) => tag_ingredient(tag),
+            None => item(name).map(|item| Ingredient { name: short_name(name).to_string(), items: vec![item] }),
+        },
+        IngredientJson::Item { item: name } => ingredient(&IngredientJson::Name(name.clone())),
+        IngredientJson::Tag { tag } => tag_ingredient(tag),
+        IngredientJson::AnyOf(choices) => {
+            let choices = choices.iter().map(ingredient).collect::<Option<Vec<_>>>()?;
+            Some(Ingredient {
+                name: choices.iter().map(|choice| choice.name.as_str()).collect::<Vec<_>>().join("/"),
+                items: choices.into_iter().flat_map(|choice| choice.items).collect(),
+            })
+        }
+    }
+}
+
+fn tag_ingredient(tag: &str) -> Option<Ingredient> {
+    let tag = short_name(tag);
+    let (_, items) = ITEM_TAGS.iter().find(|(name, _)| *name == tag)?;
+    let mut items = items.iter().copied().collect::<Vec<_>>();
+    // tags are sets, keep the order the same on every run
+    items.sort_by_key(|item| item.to_string());
+    Some(Ingredient { name: format!("#{tag}"), items })
+}
+
+fn item(name: &str) -> Option<Item> {
+    format!("minecraft:{}", short_name(name)).parse().ok()
+}
+
+fn short_name(name: &str) -> &str {
+    name.strip_prefix("minecraft:").unwrap_or(name)
+}
+
+/// Recipes that make `item`
+pub fn for_item(item: Item) -> &'static [Recipe] {
+    RECIPES.get().and_then(|recipes| recipes.get(&item)).map_or(&[], Vec::as_slice)
+}
+
+/// Names of the items there are recipes for, without the namespace
+pub fn item_names() -> Vec<String> {
+    let Some(recipes) = RECIPES.get() else {
+        return Vec::new();
+    };
+    recipes.keys().map(|item| short_name(&item.to_string()).to_string()).collect()
+}
+
+/// Crafting tables from the config
+pub fn tables() -> &'static [BlockPos] {
+    TABLES.get().map_or(&[], Vec::as_slice)
+}
+
+/// Resolves `stick` or `minecraft:stick`, with suggestions for typos
+pub fn resolve_item(query: &str) -> Result<Item, String> {
+    let name = query.trim().to_lowercase();
+    let name = short_name(&name);
+    item(name).ok_or_else(|| {
+        let suggestions = suggest(name, registry_names::<Item>());
+        if suggestions.is_empty() {
+            format!("unknown item `{name}`")
+        } else {
+            format!("unknown item `{name}`, did you mean {}?", suggestions.join(", "))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Option<Recipe> {
+        recipe("test".into(), serde_json::from_str(json).unwrap())
+    }
+
+    fn grid_slots(recipe: &Recipe, grid_width: usize) -> Vec<(usize, &str)> {
+        recipe.placement(grid_width).into_iter().map(|(slot, ingredient)| (slot, ingredient.name.as_str())).collect()
+    }
+
+    #[test]
+    fn shaped_with_strings() {
+        let recipe = parse(
+            r####"{
+                "type": "minecraft:crafting_shaped",
+                "key": { "#": "minecraft:stick", "X": "#minecraft:planks" },
+                "pattern": ["XXX", " # ", " # "],
+                "result": { "id": "minecraft:wooden_pickaxe", "count": 1 }
+            }"####,
+        )
+        .unwrap();
+        assert_eq!(recipe.result, Item::WoodenPickaxe);
+        assert_eq!(recipe.count, 1);
+        assert!(!recipe.fits_inventory());
+        assert_eq!(
+            grid_slots(&recipe, 3),
+            [(1, "#planks"), (2, "#planks"), (3, "#planks"), (5, "stick"), (8, "stick")]
+        );
+        let ingredients = recipe.ingredients();
+        assert_eq!(ingredients.len(), 2);
+        assert_eq!((ingredients[0].0.name.as_str(), ingredients[0].1), ("#planks", 3));
+        assert!(ingredients[0].0.items.contains(&Item::OakPlanks));
+        assert_eq!((ingredients[1].0.name.as_str(), ingredients[1].1), ("stick", 2));
+    }
+
+    #[test]
+    fn shaped_with_objects() {
+        let recipe = parse(
+            r####"{
+                "type": "minecraft:crafting_shaped",
+                "key": { "#": { "tag": "minecraft:planks" } },
+                "pattern": ["#", "#"],
+                "result": { "item": "minecraft:stick", "count": 4 }
+            }"####,
+        )
+        .unwrap();
+        assert_eq!(recipe.result, Item::Stick);
+        assert_eq!(recipe.count, 4);
+        assert!(recipe.fits_inventory());
+        assert_eq!(grid_slots(&recipe, 2), [(1, "#planks"), (3, "#planks")]);
+        assert_eq!(grid_slots(&recipe, 3), [(1, "#planks"), (4, "#planks")]);
+    }
+
+    #[test]
+    fn shaped_with_choices_and_gaps() {
+        let recipe = parse(
+            r####"{
+                "type": "minecraft:crafting_shaped",
+                "key": {
+                    "#": { "item": "minecraft:stick" },
+                    "X": [{ "item": "minecraft:coal" }, { "item": "minecraft:charcoal" }]
+                },
+                "pattern": ["X", "#"],
+                "result": { "item": "minecraft:torch", "count": 4 }
+            }"####,
+        )
+        .unwrap();
+        let slots = recipe.placement(3);
+        assert_eq!(slots[0].1.name, "coal/charcoal");
+        assert_eq!(slots[0].1.items, [Item::Coal, Item::Charcoal]);
+
+        let wide = parse(
+            r####"{
+                "type": "minecraft:crafting_shaped",
+                "key": { "#": "minecraft:cobblestone" },
+                "pattern": ["###", "# #", "###"],
+                "result": { "id": "minecraft:furnace" }
+            }"####,
+        )
+        .unwrap();
+        assert_eq!(wide.count, 1);
+        assert!(!wide.fits_inventory());
+        assert_eq!(grid_slots(&wide, 3).iter().map(|(slot, _)| *slot).collect::<Vec<_>>(), [1, 2, 3, 4, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn shapeless_with_strings() {
+        let recipe = parse(
+            r####"{
+                "type": "minecraft:crafting_shapeless",
+                "ingredients": ["#minecraft:oak_logs"],
+                "result": { "id": "minecraft:oak_planks", "count": 4 }
+            }"####,
+        )
+        .unwrap();
+        assert_eq!((recipe.result, recipe.count), (Item::OakPlanks, 4));
+        assert!(recipe.fits_inventory());
+        assert_eq!(grid_slots(&recipe, 2), [(1, "#oak_logs")]);
+    }
+
+    #[test]
+    fn shapeless_with_objects_and_no_count() {
+        let recipe = parse(
+            r####"{
+                "type": "minecraft:crafting_shapeless",
+                "ingredients": [{ "item": "minecraft:iron_ingot" }, { "item": "minecraft:flint" }],
+                "result": { "item": "minecraft:flint_and_steel" }
+            }"####,
+        )
+        .unwrap();
+        assert_eq!((recipe.result, recipe.count), (Item::FlintAndSteel, 1));
+        assert!(recipe.fits_inventory());
+        assert_eq!(grid_slots(&recipe, 2), [(1, "iron_ingot"), (2, "flint")]);
+        assert_eq!(grid_slots(&recipe, 3), [(1, "iron_ingot"), (2, "flint")]);
+    }
+
+    #[test]
+    fn skips_other_recipes() {
+        let smelting = r####"{
+            "type": "minecraft:smelting",
+            "ingredient": "minecraft:iron_ore",
+            "result": { "id": "minecraft:iron_ingot" }
+        }"####;
+        assert!(parse(smelting).is_none());
+        let unknown_item = r####"{
+            "type": "minecraft:crafting_shapeless",
+            "ingredients": ["minecraft:not_an_item"],
+            "result": { "id": "minecraft:stick" }
+        }"####;
+        assert!(parse(unknown_item).is_none());
+    }
+}
@@ -0,0 +1,269 @@
+// Rejoins bots that got kicked or lost their connection. The bot entity, and
+// with it the module settings and the task queue, stays in the ECS while the
+// bot is gone, so a rejoined bot picks up where it left off.
+
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, RandomState},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use azalea::{ecs::prelude::*, prelude::*, swarm::prelude::*, JoinError, JoinOpts};
+use parking_lot::Mutex;
+
+use super::{bridge::LogChannel, State};
+use crate::config::ReconnectConfig;
+
+#[derive(Clone, Default, Resource)]
+pub struct SwarmState {
+    pub reconnect: ReconnectConfig,
+    /// Every bot by account name, with the state it joins with
+    pub bots: Arc<Mutex<HashMap<String, BotConnection>>>,
+    pub log: LogChannel,
+}
+
+pub struct BotConnection {
+    pub state: State,
+    /// Failed joins since the last good connection
+    attempts: u32,
+    joined_at: Instant,
+    /// Why the bot left on purpose and must not rejoin
+    offline_reason: Option<String>,
+}
+
+impl BotConnection {
+    pub fn new(state: State) -> Self {
+        Self { state, attempts: 0, joined_at: Instant::now(), offline_reason: None }
+    }
+}
+
+impl SwarmState {
+    /// Keeps `name` from reconnecting after it left on purpose
+    pub fn stay_offline(&self, name: &str, reason: String) {
+        if let Some(bot) = self.bots.lock().get_mut(name) {
+            bot.offline_reason = Some(reason);
+        }
+    }
+}
+
+pub async fn swarm_handle(swarm: Swarm, event: SwarmEvent, state: SwarmState) -> color_eyre::Result<()> {
+    if let SwarmEvent::Disconnect(account, join_opts) = event {
+        reconnect(&swarm, &account, &join_opts, &state).await;
+    }
+    Ok(())
+}
+
+async fn reconnect(swarm: &Swarm, account: &Account, join_opts: &JoinOpts, swarm_state: &SwarmState) {
+    let join = move |state: State| async move { swarm.add_with_opts(account, state, join_opts).await.map(drop) };
+    rejoin(swarm_state, &account.username, join).await;
+}
+
+/// Waits out the backoff before every call to `join` until one works, returns
+/// whether the bot is back
+async fn rejoin<F, Fut>(swarm_state: &SwarmState, name: &str, mut join: F) -> bool
+where
+    F: FnMut(State) -> Fut,
+    Fut: Future<Output = Result<(), JoinError>>,
+{
+    let config = &swarm_state.reconnect;
+    let log = &swarm_state.log;
+    if !config.enabled {
+        log.bot_log(name, "not reconnecting, reconnect is disabled");
+        return false;
+    }
+    let state = {
+        let mut bots = swarm_state.bots.lock();
+        let Some(bot) = bots.get_mut(name) else {
+            log.console(format!("error: {name} disconnected but is not in the swarm"));
+            return false;
+        };
+        if let Some(reason) = &bot.offline_reason {
+            log.bot_log(name, format!("not reconnecting, {reason}"));
+            return false;
+        }
+        if bot.joined_at.elapsed().as_secs_f64() >= config.stable_after_secs {
+            bot.attempts = 0;
+        }
+        bot.state.clone()
+    };
+
+    loop {
+        let attempts = bump_attempts(swarm_state, name);
+        if config.max_attempts != 0 && attempts > config.max_attempts {
+            log.bot_log(name, format!("giving up after {} failed reconnects", config.max_attempts));
+            return false;
+        }
+        let delay = backoff(config, attempts);
+        log.bot_log(name, format!("reconnecting in {:.1}s (attempt {attempts})", delay.as_secs_f64()));
+        tokio::time::sleep(delay).await;
+
+        match join(state.clone()).await {
+            Ok(()) => {
+                if let Some(bot) = swarm_state.bots.lock().get_mut(name) {
+                    bot.joined_at = Instant::now();
+                }
+                return true;
+            }
+            Err(JoinError::Disconnect { reason }) => log.bot_log(name, format!("kicked while joining: {reason}")),
+            Err(e) => log.bot_log(name, format!("could not join: {e}")),
+        }
+    }
+}
+
+fn bump_attempts(swarm_state: &SwarmState, name: &str) -> u32 {
+    let mut bots = swarm_state.bots.lock();
+    let Some(bot) = bots.get_mut(name) else {
+        return 1;
+    };
+    bot.attempts += 1;
+    bot.attempts
+}
+
+/// `initial_delay_secs` doubled for every earlier attempt, capped at
+/// `max_delay_secs`, then spread by up to `jitter` either way
+fn backoff(config: &ReconnectConfig, attempt: u32) -> Duration {
+    let doublings = attempt.saturating_sub(1).min(30) as i32;
+    let delay = (config.initial_delay_secs * 2f64.powi(doublings)).min(config.max_delay_secs);
+    let spread = config.jitter * (random_unit() * 2.0 - 1.0);
+    // huge delays from the config wait forever instead of panicking
+    Duration::try_from_secs_f64((delay * (1.0 + spread)).max(0.0)).unwrap_or(Duration::MAX)
+}
+
+/// Random number in `0..=1`, the std hasher is randomly seeded so no rand
+/// crate is needed for a bit of jitter
+fn random_unit() -> f64 {
+    RandomState::new().hash_one(Instant::now()) as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    };
+
+    use azalea::{
+        auth::game_profile::GameProfile,
+        protocol::{
+            connect::Connection,
+            packets::{
+                handshake::{ClientboundHandshakePacket, ServerboundHandshakePacket},
+                login::{ClientboundLoginDisconnect, ClientboundLoginFinished},
+            },
+        },
+    };
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::azal::{bridge::LogChannel, ConsoleType};
+
+    fn config(initial_delay_secs: f64, max_delay_secs: f64, jitter: f64) -> ReconnectConfig {
+        ReconnectConfig { initial_delay_secs, max_delay_secs, jitter, ..Default::default() }
+    }
+
+    /// A swarm state with one bot, and what it writes to the Bot Log
+    fn swarm_state(name: &str, reconnect: ReconnectConfig) -> (SwarmState, mpsc::Receiver<ConsoleType>) {
+        let (tx, rx) = mpsc::channel();
+        let state = SwarmState { reconnect, log: LogChannel::new(tx), ..Default::default() };
+        state.bots.lock().insert(name.to_string(), BotConnection::new(State::default()));
+        (state, rx)
+    }
+
+    fn bot_log(rx: &mpsc::Receiver<ConsoleType>) -> Vec<String> {
+        rx.try_iter()
+            .filter_map(|entry| match entry {
+                ConsoleType::Botlog { msg, .. } => Some(msg),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let config = config(1.0, 100.0, 0.0);
+        let delays = (1..=4).map(|attempt| backoff(&config, attempt).as_secs_f64()).collect::<Vec<_>>();
+        assert_eq!(delays, [1.0, 2.0, 4.0, 8.0]);
+    }
+
+    #[test]
+    fn backoff_stops_at_max_delay() {
+        let config = config(1.0, 30.0, 0.0);
+        assert_eq!(backoff(&config, 6).as_secs_f64(), 30.0);
+        assert_eq!(backoff(&config, u32::MAX).as_secs_f64(), 30.0);
+    }
+
+    #[test]
+    fn backoff_jitter_stays_in_range() {
+        let config = config(10.0, 10.0, 0.2);
+        for attempt in 1..1000 {
+            let delay = backoff(&config, attempt).as_secs_f64();
+            assert!((8.0..=12.0).contains(&delay), "{delay}s is more than 20% off 10s");
+        }
+    }
+
+    /// Plays a server that kicks the first login, drops the second connection
+    /// and lets the third one in
+    async fn stand_in_server(listener: TcpListener, connections: Arc<AtomicUsize>) {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let connection = connections.fetch_add(1, Ordering::SeqCst);
+            if connection == 1 {
+                drop(stream);
+                continue;
+            }
+            let mut conn = Connection::<ServerboundHandshakePacket, ClientboundHandshakePacket>::wrap(stream);
+            conn.read().await.unwrap();
+            let mut conn = conn.login();
+            conn.read().await.unwrap();
+            if connection == 0 {
+                conn.write(ClientboundLoginDisconnect { reason: "server full".into() }).await.unwrap();
+            } else {
+                let game_profile = GameProfile::new(Account::offline("unyx").uuid_or_offline(), "unyx".into());
+                conn.write(ClientboundLoginFinished { game_profile }).await.unwrap();
+            }
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejoins_with_backoff_after_kicks_and_drops() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let connections = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(stand_in_server(listener, connections.clone()));
+
+        let (state, rx) = swarm_state("unyx", config(0.1, 1.0, 0.0));
+        let started = Instant::now();
+        let join = |_| {
+            let address = address.clone();
+            async move { Client::join(&Account::offline("unyx"), address.as_str()).await.map(drop) }
+        };
+        assert!(rejoin(&state, "unyx", join).await);
+
+        assert!(started.elapsed() >= Duration::from_secs_f64(0.1 + 0.2 + 0.4));
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+        let log = bot_log(&rx);
+        assert_eq!(log[0], "reconnecting in 0.1s (attempt 1)");
+        assert_eq!(log[1], "kicked while joining: server full");
+        assert_eq!(log[2], "reconnecting in 0.2s (attempt 2)");
+        assert!(log[3].starts_with("could not join"), "{}", log[3]);
+        assert_eq!(log[4], "reconnecting in 0.4s (attempt 3)");
+        assert_eq!(log.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn stay_offline_suppresses_retries() {
+        let (state, rx) = swarm_state("unyx", config(0.1, 1.0, 0.0));
+        state.stay_offline("unyx", "health dropped to 3.0".into());
+        let joins = AtomicUsize::new(0);
+        let join = |_| {
+            joins.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        };
+        assert!(!rejoin(&state, "unyx", join).await);
+        assert_eq!(joins.load(Ordering::SeqCst), 0);
+        assert_eq!(bot_log(&rx), ["not reconnecting, health dropped to 3.0"]);
+    }
+}
@@ -0,0 +1,393 @@
+// What a script can call. Everything that takes time (walking, mining,
+// waiting) blocks the script's own thread on game ticks and checks on every
+// tick whether the script was stopped.
+//
+//   log(msg), print(msg)          line in the bot log and the script's log
+//   chat(msg)                     chat message or /command
+//   goto(x, y, z) goto(x, z) goto(waypoint)
+//                                 walks there as a task, true once arrived
+//   mine(block, count)            mines like `mine`, blocks until done, false if
+//                                 the task failed
+//   attack(kind)                  hits the closest `kind` in reach, true if any
+//   position()                    #{x, y, z} of the feet
+//   block(x, y, z)                block name, "" when not loaded
+//   health(), food()
+//   held()                        item in hand, "" when empty
+//   count(item)                   how many of `item` the inventory holds, a
+//                                 #tag or #group counts the items of its blocks
+//   inventory()                   [#{slot, item, count}] of every filled slot
+//   wait(ticks)
+//   wait_chat(), wait_chat(ticks) oldest unread #{sender, message}, () on timeout
+//   wait_tasks()                  until the task queue is empty
+//   bot, args                     the bot's name, words after `run <script>`
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, TryRecvError},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use azalea::{
+    ecs::prelude::*,
+    entity::{metadata::Health, Dead, EntityKind, LocalEntity, Position},
+    inventory::{Inventory, Player},
+    prelude::*,
+    registry::Block,
+    world::{InstanceName, MinecraftEntityId},
+    BlockPos, Hunger, Vec3,
+};
+use parking_lot::Mutex;
+use rhai::{
+    module_resolvers::DummyModuleResolver, Array, Dynamic, Engine, EvalAltResult, Map, Position as ScriptPos, Scope, INT,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::dir;
+use crate::azal::{
+    block_names,
+    bridge::{BotLog, LogChannel},
+    command::GotoTarget,
+    inventory::{item_view, InventoryView},
+    tasks::{mine::MineJob, with_tasks, Priority, TaskKind},
+    waypoints,
+};
+
+/// How far `attack` reaches
+const ATTACK_RANGE: f64 = 4.5;
+/// How close to a goto target counts as arrived
+const ARRIVED_DISTANCE: f64 = 2.0;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// A chat line as `wait_chat` returns it
+#[derive(Clone, Debug)]
+pub struct ChatLine {
+    pub sender: Option<String>,
+    pub message: String,
+}
+
+/// One run of a script, shared by every function it calls
+pub struct ScriptCtx {
+    pub bot: Client,
+    pub name: String,
+    pub script: String,
+    pub id: u32,
+    log: LogChannel,
+    /// The script's own log, `None` when it can't be written
+    file: Mutex<Option<File>>,
+    stop: Arc<AtomicBool>,
+    chat: Mutex<Receiver<ChatLine>>,
+    ticks: Mutex<broadcast::Receiver<()>>,
+}
+
+impl ScriptCtx {
+    pub fn new(
+        bot: Client,
+        name: String,
+        script: String,
+        id: u32,
+        log: LogChannel,
+        stop: Arc<AtomicBool>,
+        chat: Receiver<ChatLine>,
+    ) -> Self {
+        let logs = dir().join("logs");
+        let file = fs::create_dir_all(&logs)
+            .and_then(|()| OpenOptions::new().create(true).append(true).open(logs.join(format!("{script}.log"))));
+        let file = match file {
+            Ok(file) => Some(file),
+            Err(e) => {
+                log.bot_log(&name, format!("[{script}#{id}] can't write the script log: {e}"));
+                None
+            }
+        };
+        let ticks = bot.get_tick_broadcaster();
+        Self {
+            bot,
+            name,
+            script,
+            id,
+            log,
+            file: Mutex::new(file),
+            stop,
+            chat: Mutex::new(chat),
+            ticks: Mutex::new(ticks),
+        }
+    }
+
+    fn write_file(&self, msg: &str) {
+        if let Some(file) = &mut *self.file.lock() {
+            let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+            let _ = writeln!(file, "{secs} {}#{}: {msg}", self.name, self.id);
+        }
+    }
+
+    pub fn log(&self, msg: &str) {
+        self.write_file(msg);
+        self.log.bot_log(&self.name, format!("[{}#{}] {msg}", self.script, self.id));
+    }
+
+    /// Last line of a run, also whispered to the player who started it
+    pub fn finish(&self, outcome: &str, from: Option<String>) {
+        self.write_file(outcome);
+        let line = format!("script {}#{} {outcome}", self.script, self.id);
+        match from {
+            Some(player) => {
+                let whisper = Some((self.bot.entity, player));
+                self.bot.ecs.lock().send_event(BotLog { whisper, ..BotLog::new(&self.name, line) });
+            }
+            None => self.log.bot_log(&self.name, line),
+        }
+    }
+
+    fn stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Waits for the next game tick, ending the script if it was stopped
+    fn tick(&self) -> ScriptResult<()> {
+        match self.ticks.lock().blocking_recv() {
+            Ok(()) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return Err("the swarm stopped ticking".into()),
+        }
+        if self.stopped() {
+            return Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, ScriptPos::NONE).into());
+        }
+        Ok(())
+    }
+
+    fn component<T: Component + Clone>(&self) -> ScriptResult<T> {
+        self.bot.get_component::<T>().ok_or_else(|| "the bot is not in a world".into())
+    }
+
+    fn position(&self) -> ScriptResult<Vec3> {
+        self.component::<Position>().map(|position| *position)
+    }
+
+    /// Queues a task and waits until the runner is done with it, false when
+    /// it failed, a stopped script cancels its task
+    fn run_task(&self, kind: TaskKind) -> ScriptResult<bool> {
+        let id = with_tasks(&self.bot, |tasks| tasks.push(kind.clone(), Priority::Normal));
+        self.log(&format!("queued task #{id}: {kind}"));
+        let queued = || with_tasks(&self.bot, |tasks| tasks.tasks.iter().any(|task| task.id == id));
+        while queued() {
+            if let Err(e) = self.tick() {
+                with_tasks(&self.bot, |tasks| tasks.tasks.retain(|task| task.id != id));
+                return Err(e);
+            }
+        }
+        Ok(with_tasks(&self.bot, |tasks| tasks.failure(id).is_none()))
+    }
+
+    fn goto(&self, target: GotoTarget) -> ScriptResult<bool> {
+        self.run_task(TaskKind::Goto(target.clone()))?;
+        let position = self.position()?;
+        Ok(match target {
+            GotoTarget::Block(pos) => position.distance_to(&pos.center()) <= ARRIVED_DISTANCE,
+            GotoTarget::Column { x, z } => {
+                let (dx, dz) = (position.x - (f64::from(x) + 0.5), position.z - (f64::from(z) + 0.5));
+                dx.hypot(dz) <= ARRIVED_DISTANCE
+            }
+        })
+    }
+
+    fn attack(&self, kind: &str) -> ScriptResult<bool> {
+        let eyes = self.position()?;
+        let instance = self.component::<InstanceName>()?;
+        let kind = kind.trim_start_matches("minecraft:").to_lowercase();
+        let target = {
+            let mut ecs = self.bot.ecs.lock();
+            let mut query = ecs.query_filtered::<(&MinecraftEntityId, &EntityKind, &Position, &InstanceName), (
+                Without<LocalEntity>,
+                Without<Dead>,
+            )>();
+            query
+                .iter(&ecs)
+                .filter(|(_, entity_kind, _, entity_instance)| {
+                    **entity_instance == instance && entity_kind.to_string().trim_start_matches("minecraft:") == kind
+                })
+                .map(|(id, _, position, _)| (*id, position.distance_to(&eyes)))
+                .filter(|(_, distance)| *distance <= ATTACK_RANGE)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+        };
+        let Some((id, _)) = target else {
+            return Ok(false);
+        };
+        self.bot.attack(id);
+        Ok(true)
+    }
+
+    fn block(&self, x: INT, y: INT, z: INT) -> ScriptResult<String> {
+        let pos = BlockPos::new(int(x)?, int(y)?, int(z)?);
+        let state = self.bot.world().read().get_block_state(&pos);
+        Ok(state.map(|state| item_name(&Block::from(state).to_string())).unwrap_or_default())
+    }
+
+    fn inventory(&self) -> ScriptResult<Vec<(usize, String, i32)>> {
+        let inventory = self.component::<Inventory>()?;
+        let view = InventoryView::new(&inventory);
+        Ok(view
+            .player
+            .into_iter()
+            .enumerate()
+            .filter(|(slot, _)| Player::INVENTORY_SLOTS.contains(slot) || *slot == Player::OFFHAND_SLOT)
+            .filter_map(|(slot, item)| item.map(|(name, count)| (slot, name, count)))
+            .collect())
+    }
+
+    fn held(&self) -> ScriptResult<String> {
+        let inventory = self.component::<Inventory>()?;
+        Ok(item_view(&inventory.held_item()).map(|(name, _)| name).unwrap_or_default())
+    }
+
+    fn wait_chat(&self, max_ticks: Option<INT>) -> ScriptResult<Dynamic> {
+        let mut waited = 0;
+        loop {
+            match self.chat.lock().try_recv() {
+                Ok(ChatLine { sender, message }) => {
+                    let mut line = Map::new();
+                    line.insert("sender".into(), sender.map_or(Dynamic::UNIT, Dynamic::from));
+                    line.insert("message".into(), message.into());
+                    return Ok(line.into());
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => return Err("the bot stopped listening to chat".into()),
+            }
+            if max_ticks.is_some_and(|max| waited >= max) {
+                return Ok(Dynamic::UNIT);
+            }
+            self.tick()?;
+            waited += 1;
+        }
+    }
+}
+
+fn int(value: INT) -> ScriptResult<i32> {
+    i32::try_from(value).map_err(|_| format!("{value} is out of range").into())
+}
+
+fn item_name(name: &str) -> String {
+    name.trim_start_matches("minecraft:").to_string()
+}
+
+/// The variables every script starts with
+pub fn scope(ctx: &ScriptCtx, args: Vec<String>) -> Scope<'static> {
+    let mut scope = Scope::new();
+    scope.push_constant("bot", ctx.name.clone());
+    scope.push_constant("args", args.into_iter().map(Dynamic::from).collect::<Array>());
+    scope
+}
+
+/// An engine that can only reach the bot through `ctx`
+pub fn engine(ctx: Arc<ScriptCtx>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_call_levels(64)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(64 * 1024)
+        .set_max_array_size(10_000)
+        .set_max_map_size(10_000);
+    engine.disable_symbol("eval");
+
+    let c = ctx.clone();
+    engine.on_progress(move |_| c.stopped().then_some(Dynamic::UNIT));
+    let c = ctx.clone();
+    engine.on_print(move |msg| c.log(msg));
+    let c = ctx.clone();
+    engine.on_debug(move |msg, _, _| c.log(msg));
+    let c = ctx.clone();
+    engine.register_fn("log", move |msg: &str| c.log(msg));
+
+    let c = ctx.clone();
+    engine.register_fn("chat", move |msg: &str| c.bot.chat(msg));
+
+    let c = ctx.clone();
+    engine.register_fn("goto", move |x: INT, y: INT, z: INT| {
+        c.goto(GotoTarget::Block(BlockPos::new(int(x)?, int(y)?, int(z)?)))
+    });
+    let c = ctx.clone();
+    engine.register_fn("goto", move |x: INT, z: INT| c.goto(GotoTarget::Column { x: int(x)?, z: int(z)? }));
+    let c = ctx.clone();
+    engine.register_fn("goto", move |waypoint: &str| -> ScriptResult<bool> {
+        let pos = waypoints::get(waypoint).ok_or_else(|| format!("no waypoint named `{waypoint}`"))?;
+        c.goto(GotoTarget::Block(pos))
+    });
+    let c = ctx.clone();
+    engine.register_fn("mine", move |block: &str, count: INT| -> ScriptResult<bool> {
+        let blocks = block_names::resolve(block).map_err(|e| e.to_string())?;
+        let quantity = usize::try_from(count).ok().filter(|count| *count > 0).ok_or("count must be at least 1")?;
+        let job = MineJob { block_states: blocks.block_states(), label: blocks.label, quantity, mined: 0 };
+        c.run_task(TaskKind::Mine(job))
+    });
+    let c = ctx.clone();
+    engine.register_fn("attack", move |kind: &str| c.attack(kind));
+
+    let c = ctx.clone();
+    engine.register_fn("position", move || -> ScriptResult<Map> {
+        let position = c.position()?;
+        let mut map = Map::new();
+        map.insert("x".into(), position.x.into());
+        map.insert("y".into(), position.y.into());
+        map.insert("z".into(), position.z.into());
+        Ok(map)
+    });
+    let c = ctx.clone();
+    engine.register_fn("block", move |x: INT, y: INT, z: INT| c.block(x, y, z));
+    let c = ctx.clone();
+    engine.register_fn("health", move || c.component::<Health>().map(|health| f64::from(*health)));
+    let c = ctx.clone();
+    engine.register_fn("food", move || c.component::<Hunger>().map(|hunger| INT::from(hunger.food)));
+
+    let c = ctx.clone();
+    engine.register_fn("held", move || c.held());
+    let c = ctx.clone();
+    engine.register_fn("count", move |item: &str| -> ScriptResult<INT> {
+        // block tags and groups stand for the items of the same name, like the logs
+        let items = if item.starts_with('#') {
+            let blocks = block_names::resolve(item).map_err(|e| e.to_string())?.blocks;
+            blocks.iter().map(|block| item_name(&block.to_string())).collect()
+        } else {
+            vec![item_name(&item.to_lowercase())]
+        };
+        let inventory = c.inventory()?.into_iter();
+        Ok(inventory.filter(|(_, name, _)| items.contains(name)).map(|(_, _, count)| INT::from(count)).sum())
+    });
+    let c = ctx.clone();
+    engine.register_fn("inventory", move || -> ScriptResult<Array> {
+        Ok(c.inventory()?
+            .into_iter()
+            .map(|(slot, item, count)| {
+                let mut map = Map::new();
+                map.insert("slot".into(), (slot as INT).into());
+                map.insert("item".into(), item.into());
+                map.insert("count".into(), INT::from(count).into());
+                map.into()
+            })
+            .collect())
+    });
+
+    let c = ctx.clone();
+    engine.register_fn("wait", move |ticks: INT| -> ScriptResult<()> {
+        for _ in 0..ticks {
+            c.tick()?;
+        }
+        Ok(())
+    });
+    let c = ctx.clone();
+    engine.register_fn("wait_chat", move || c.wait_chat(None));
+    let c = ctx.clone();
+    engine.register_fn("wait_chat", move |ticks: INT| c.wait_chat(Some(ticks)));
+    let c = ctx;
+    engine.register_fn("wait_tasks", move || -> ScriptResult<()> {
+        while with_tasks(&c.bot, |tasks| !tasks.tasks.is_empty()) {
+            c.tick()?;
+        }
+        Ok(())
+    });
+    engine
+}
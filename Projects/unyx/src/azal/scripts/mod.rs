@@ -0,0 +1,242 @@
+// Rhai scripts from the scripts directory. `run` queues a script in a
+// component on the bot entity and the handler starts it on its next tick, on
+// a thread of its own since Rhai can't await. Scripts only get the calls in
+// `api`, no files, no imports.
+
+pub mod api;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{sync_channel, SyncSender},
+        Arc,
+        OnceLock,
+    },
+};
+
+use azalea::{
+    app::{App, Plugin, Update},
+    chat::ChatReceivedEvent,
+    ecs::prelude::*,
+    entity::LocalEntity,
+    prelude::*,
+};
+
+use super::{
+    bridge::{BotCommand, BotLog, BridgePlugin, LogChannel},
+    command::{CommandType, ScriptCommand, TaskSelector},
+};
+use crate::config::ScriptsConfig;
+use api::{ChatLine, ScriptCtx};
+use rhai::EvalAltResult;
+
+/// Chat lines kept for a script that is not waiting for one right now
+const CHAT_BACKLOG: usize = 64;
+
+/// Where scripts are read from, set once from the config
+static SCRIPTS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Stores the `[scripts]` directory of the config
+pub fn load(config: &ScriptsConfig) -> Result<(), String> {
+    SCRIPTS_DIR.set(config.dir.clone()).map_err(|_| "the scripts directory was already set".to_string())
+}
+
+fn dir() -> &'static Path {
+    SCRIPTS_DIR.get().map_or(Path::new("scripts"), PathBuf::as_path)
+}
+
+fn path_of(script: &str) -> PathBuf {
+    dir().join(format!("{script}.rhai"))
+}
+
+/// Checks a script name typed after `run`, the name is the file name
+/// without `.rhai`
+pub fn check_name(script: &str) -> Result<(), String> {
+    if script.is_empty() || !script.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("`{script}` is not a script name, only letters, digits, `_` and `-`"));
+    }
+    if !path_of(script).is_file() {
+        return Err(format!("no script {}", path_of(script).display()));
+    }
+    Ok(())
+}
+
+/// Every script in the scripts directory, sorted
+pub fn names() -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir()) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "rhai").then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+pub struct ScriptsPlugin;
+
+impl Plugin for ScriptsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            GameTick,
+            (Self::add_runs, Self::handle_commands).chain().after(BridgePlugin::drain_commands),
+        )
+        .add_systems(Update, Self::forward_chat);
+    }
+}
+
+/// Scripts of one bot, queued or running
+#[derive(Component, Default)]
+pub struct ScriptRuns {
+    runs: Vec<ScriptRun>,
+    next_id: u32,
+}
+
+struct ScriptRun {
+    id: u32,
+    script: String,
+    args: Vec<String>,
+    /// Player who started it from chat, told when it ends
+    from: Option<String>,
+    started: bool,
+    stop: Arc<AtomicBool>,
+    chat: Option<SyncSender<ChatLine>>,
+}
+
+impl ScriptRuns {
+    fn selected(&self, selector: TaskSelector) -> impl Iterator<Item = &ScriptRun> {
+        self.runs.iter().filter(move |run| match selector {
+            TaskSelector::All => true,
+            TaskSelector::Id(id) => run.id == id,
+        })
+    }
+}
+
+impl ScriptsPlugin {
+    fn add_runs(query: Query<Entity, (With<LocalEntity>, Without<ScriptRuns>)>, mut commands: Commands) {
+        for entity in &query {
+            commands.entity(entity).insert(ScriptRuns::default());
+        }
+    }
+
+    /// Queues `run`, handles `scripts`
+    fn handle_commands(
+        mut events: EventReader<BotCommand>,
+        mut query: Query<&mut ScriptRuns>,
+        mut log: EventWriter<BotLog>,
+    ) {
+        for event in events.read() {
+            let BotCommand { entity, command, .. } = event;
+            let Ok(mut scripts) = query.get_mut(*entity) else {
+                continue;
+            };
+            match command {
+                CommandType::Run { script, args } => {
+                    scripts.next_id += 1;
+                    let id = scripts.next_id;
+                    scripts.runs.push(ScriptRun {
+                        id,
+                        script: script.clone(),
+                        args: args.clone(),
+                        from: event.from.clone(),
+                        started: false,
+                        stop: Arc::new(AtomicBool::new(false)),
+                        chat: None,
+                    });
+                    log.send(event.reply(format!("starting script {script}#{id}")));
+                }
+                CommandType::Scripts(ScriptCommand::List) => {
+                    if scripts.runs.is_empty() {
+                        log.send(event.reply("no scripts running"));
+                    }
+                    for run in &scripts.runs {
+                        let args = run.args.join(" ");
+                        log.send(event.reply(format!("#{} {} {args}", run.id, run.script).trim_end().to_string()));
+                    }
+                }
+                CommandType::Scripts(ScriptCommand::Stop(selector)) => {
+                    let mut stopped = Vec::new();
+                    for run in scripts.selected(*selector) {
+                        // the script ends on its next step, the runner removes it
+                        if !run.stop.swap(true, Ordering::Relaxed) {
+                            stopped.push(format!("{}#{}", run.script, run.id));
+                        }
+                    }
+                    let line = match (stopped.is_empty(), selector) {
+                        (false, _) => format!("stopping {}", stopped.join(", ")),
+                        (true, TaskSelector::All) => "no scripts running".into(),
+                        (true, TaskSelector::Id(id)) => format!("script #{id} is not running"),
+                    };
+                    log.send(event.reply(line));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Hands chat lines to the running scripts of the bot that heard them
+    fn forward_chat(mut events: EventReader<ChatReceivedEvent>, query: Query<&ScriptRuns>) {
+        for ChatReceivedEvent { entity, packet } in events.read() {
+            let Ok(scripts) = query.get(*entity) else {
+                continue;
+            };
+            let (sender, message) = packet.split_sender_and_content();
+            for chat in scripts.runs.iter().filter_map(|run| run.chat.as_ref()) {
+                // a script that never waits for chat just misses lines
+                let _ = chat.try_send(ChatLine { sender: sender.clone(), message: message.clone() });
+            }
+        }
+    }
+}
+
+/// Starts the scripts queued since the last tick
+pub fn poll(bot: &Client, name: &str) {
+    let starting = {
+        let mut ecs = bot.ecs.lock();
+        let Some(mut scripts) = ecs.get_mut::<ScriptRuns>(bot.entity) else {
+            return;
+        };
+        let mut starting = Vec::new();
+        for run in scripts.runs.iter_mut().filter(|run| !run.started) {
+            run.started = true;
+            let (tx, rx) = sync_channel(CHAT_BACKLOG);
+            run.chat = Some(tx);
+            starting.push((run.id, run.script.clone(), run.args.clone(), run.from.clone(), run.stop.clone(), rx));
+        }
+        starting
+    };
+    if starting.is_empty() {
+        return;
+    }
+    let log = bot.resource::<LogChannel>();
+    for (id, script, args, from, stop, chat) in starting {
+        let ctx = ScriptCtx::new(bot.clone(), name.to_string(), script, id, log.clone(), stop, chat);
+        tokio::task::spawn_blocking(move || run(ctx, args, from));
+    }
+}
+
+fn run(ctx: ScriptCtx, args: Vec<String>, from: Option<String>) {
+    let ctx = Arc::new(ctx);
+    let path = path_of(&ctx.script);
+    let line = match fs::read_to_string(&path) {
+        Err(e) => format!("could not read {}: {e}", path.display()),
+        Ok(source) => {
+            let mut scope = api::scope(&ctx, args);
+            match api::engine(ctx.clone()).run_with_scope(&mut scope, &source) {
+                Ok(()) => "finished".to_string(),
+                Err(e) if matches!(*e, EvalAltResult::ErrorTerminated(..)) => "stopped".to_string(),
+                Err(e) => format!("failed: {e}"),
+            }
+        }
+    };
+    ctx.finish(&line, from);
+    let mut ecs = ctx.bot.ecs.lock();
+    if let Some(mut scripts) = ecs.get_mut::<ScriptRuns>(ctx.bot.entity) {
+        scripts.runs.retain(|run| run.id != ctx.id);
+    }
+}
@@ -0,0 +1,326 @@
+// Runtime settings of every module. Each module keeps its settings in a
+// component on the bot entity, so the tick systems, the command systems and
+// a reconnected client all see the same values.
+
+use std::{fmt, ops::RangeInclusive};
+
+use azalea::{
+    ecs::prelude::*,
+    prelude::*,
+    registry::{EntityKind, Item},
+};
+
+use super::{
+    block_names::registry_names,
+    bridge::{BotCommand, BotLog},
+    command::CommandType,
+    modules::{
+        auto_armor::AutoArmorSettings, auto_eat::AutoEatSettings, farming::FarmingSettings, killaura::KillauraSettings,
+        safety::SafetySettings,
+    },
+    waypoints,
+};
+use crate::config::ModulesConfig;
+
+/// Every module with settings, in the order `get` lists them
+pub const MODULES: &[&str] = &["killaura", "auto_eat", "auto_armor", "safety", "farming"];
+
+/// A value that can be typed after `set <module> <key>`
+pub trait SettingValue: Sized {
+    fn parse_setting(value: &str) -> Result<Self, String>;
+    fn show(&self) -> String;
+}
+
+impl SettingValue for bool {
+    fn parse_setting(value: &str) -> Result<Self, String> {
+        match value.to_lowercase().as_str() {
+            "on" | "true" => Ok(true),
+            "off" | "false" => Ok(false),
+            _ => Err(format!("expected on or off, got `{value}`")),
+        }
+    }
+
+    fn show(&self) -> String {
+        if *self { "on".into() } else { "off".into() }
+    }
+}
+
+impl SettingValue for u32 {
+    fn parse_setting(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|_| format!("`{value}` is not a valid number"))
+    }
+
+    fn show(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// `NaN` and `inf` parse as floats too, but no setting has a use for them
+impl SettingValue for f64 {
+    fn parse_setting(value: &str) -> Result<Self, String> {
+        match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number),
+            _ => Err(format!("`{value}` is not a valid number")),
+        }
+    }
+
+    fn show(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Fails unless `value` is in `range`, which also rules out `NaN`
+pub fn check_range<T: PartialOrd + fmt::Display>(key: &str, value: &T, range: RangeInclusive<T>) -> Result<(), String> {
+    if range.contains(value) {
+        Ok(())
+    } else {
+        Err(format!("`{key}` must be between {} and {}, got {value}", range.start(), range.end()))
+    }
+}
+
+/// Comma separated, `none` clears the list
+impl SettingValue for Vec<String> {
+    fn parse_setting(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+        Ok(value.split(',').map(str::trim).filter(|entry| !entry.is_empty()).map(str::to_lowercase).collect())
+    }
+
+    fn show(&self) -> String {
+        if self.is_empty() { "none".into() } else { self.join(",") }
+    }
+}
+
+/// `none` clears it
+impl SettingValue for String {
+    fn parse_setting(value: &str) -> Result<Self, String> {
+        if value.eq_ignore_ascii_case("none") {
+            return Ok(String::new());
+        }
+        Ok(value.to_string())
+    }
+
+    fn show(&self) -> String {
+        if self.is_empty() { "none".into() } else { self.clone() }
+    }
+}
+
+/// Settings of one module, looked up by key name
+pub trait ModuleSettings: Send + Sync + 'static {
+    fn keys(&self) -> &'static [&'static str];
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+    /// Checks every value against its range, for values from the config
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Declares a settings component along with its defaults and its
+/// `ModuleSettings` impl, every field becomes a key of `set`/`get`. Numbers
+/// can be limited with `= default => min..=max`
+macro_rules! module_settings {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($(#[$field_meta:meta])* pub $field:ident: $ty:ty = $default:expr $(=> $range:expr)?,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, azalea::ecs::component::Component, serde::Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self { $($field: $default,)* }
+            }
+        }
+
+        impl $crate::azal::settings::ModuleSettings for $name {
+            fn keys(&self) -> &'static [&'static str] {
+                &[$(stringify!($field)),*]
+            }
+
+            fn get(&self, key: &str) -> Option<String> {
+                match key {
+                    $(stringify!($field) => Some($crate::azal::settings::SettingValue::show(&self.$field)),)*
+                    _ => None,
+                }
+            }
+
+            fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key {
+                    $(stringify!($field) => {
+                        let value: $ty = $crate::azal::settings::SettingValue::parse_setting(value)?;
+                        $($crate::azal::settings::check_range(stringify!($field), &value, $range)?;)?
+                        self.$field = value;
+                        Ok(())
+                    })*
+                    _ => Err(format!("unknown setting `{key}`")),
+                }
+            }
+
+            fn validate(&self) -> Result<(), String> {
+                $($($crate::azal::settings::check_range(stringify!($field), &self.$field, $range)?;)?)*
+                Ok(())
+            }
+        }
+    };
+}
+pub(crate) use module_settings;
+
+/// Keys of `module`, for checking commands before they reach a bot
+pub fn keys_of(module: &str) -> Option<&'static [&'static str]> {
+    match module {
+        "killaura" => Some(KillauraSettings::default().keys()),
+        "auto_eat" => Some(AutoEatSettings::default().keys()),
+        "auto_armor" => Some(AutoArmorSettings::default().keys()),
+        "safety" => Some(SafetySettings::default().keys()),
+        "farming" => Some(FarmingSettings::default().keys()),
+        _ => None,
+    }
+}
+
+/// Words Tab offers for a value of `module.key`, list settings take them
+/// comma separated
+pub fn value_options(module: &str, key: &str) -> Vec<String> {
+    match (module, key) {
+        ("auto_eat", "avoid") | ("auto_armor", "offhand") => registry_names::<Item>(),
+        ("safety", "home") => waypoints::names(),
+        ("killaura", "allow" | "deny") => {
+            let groups = ["monster", "animal"].map(String::from);
+            groups.into_iter().chain(registry_names::<EntityKind>()).collect()
+        }
+        _ => {
+            let default = match module {
+                "killaura" => KillauraSettings::default().get(key),
+                "auto_eat" => AutoEatSettings::default().get(key),
+                "auto_armor" => AutoArmorSettings::default().get(key),
+                "safety" => SafetySettings::default().get(key),
+                "farming" => FarmingSettings::default().get(key),
+                _ => None,
+            };
+            match default.as_deref() {
+                Some("on" | "off") => vec!["on".into(), "off".into()],
+                _ => Vec::new(),
+            }
+        }
+    }
+}
+
+/// Gives a bot the settings from the config, unless it already has some from
+/// before a reconnect
+pub fn init(bot: &Client, modules: &ModulesConfig) {
+    let mut ecs = bot.ecs.lock();
+    let mut entity = ecs.entity_mut(bot.entity);
+    if !entity.contains::<KillauraSettings>() {
+        entity.insert(modules.killaura.clone());
+    }
+    if !entity.contains::<AutoEatSettings>() {
+        entity.insert(modules.auto_eat.clone());
+    }
+    if !entity.contains::<AutoArmorSettings>() {
+        entity.insert(modules.auto_armor.clone());
+    }
+    if !entity.contains::<SafetySettings>() {
+        entity.insert(modules.safety.clone());
+    }
+    if !entity.contains::<FarmingSettings>() {
+        entity.insert(modules.farming.clone());
+    }
+}
+
+/// The settings components of one bot, missing until it logged in
+pub struct BotSettings<'a> {
+    pub killaura: Option<&'a mut KillauraSettings>,
+    pub auto_eat: Option<&'a mut AutoEatSettings>,
+    pub auto_armor: Option<&'a mut AutoArmorSettings>,
+    pub safety: Option<&'a mut SafetySettings>,
+    pub farming: Option<&'a mut FarmingSettings>,
+}
+
+impl BotSettings<'_> {
+    fn module(&mut self, module: &str) -> Option<&mut dyn ModuleSettings> {
+        match module {
+            "killaura" => self.killaura.as_deref_mut().map(|settings| settings as &mut dyn ModuleSettings),
+            "auto_eat" => self.auto_eat.as_deref_mut().map(|settings| settings as &mut dyn ModuleSettings),
+            "auto_armor" => self.auto_armor.as_deref_mut().map(|settings| settings as &mut dyn ModuleSettings),
+            "safety" => self.safety.as_deref_mut().map(|settings| settings as &mut dyn ModuleSettings),
+            "farming" => self.farming.as_deref_mut().map(|settings| settings as &mut dyn ModuleSettings),
+            _ => None,
+        }
+    }
+
+    /// `key = value` lines for `get`, all modules when `module` is None
+    pub fn get(&mut self, module: Option<&str>, key: Option<&str>) -> Vec<String> {
+        let modules = module.map_or(MODULES.to_vec(), |module| vec![module]);
+        let mut lines = Vec::new();
+        for module in modules {
+            let Some(settings) = self.module(module) else {
+                lines.push(format!("{module} has no settings yet, the bot has not logged in"));
+                continue;
+            };
+            for name in settings.keys() {
+                if key.is_none_or(|key| key == *name) {
+                    let value = settings.get(name).unwrap_or_default();
+                    lines.push(format!("{module}.{name} = {value}"));
+                }
+            }
+        }
+        lines
+    }
+
+    /// Changes one setting, returning the line to log
+    pub fn set(&mut self, module: &str, key: &str, value: &str) -> Result<String, String> {
+        let settings = self
+            .module(module)
+            .ok_or_else(|| format!("{module} has no settings yet, the bot has not logged in"))?;
+        settings.set(key, value)?;
+        Ok(format!("{module}.{key} = {}", settings.get(key).unwrap_or_default()))
+    }
+}
+
+type SettingsQuery<'a> = (
+    Option<&'a mut KillauraSettings>,
+    Option<&'a mut AutoEatSettings>,
+    Option<&'a mut AutoArmorSettings>,
+    Option<&'a mut SafetySettings>,
+    Option<&'a mut FarmingSettings>,
+);
+
+/// Handles `set` and `get`
+pub fn handle_commands(
+    mut events: EventReader<BotCommand>,
+    mut query: Query<SettingsQuery>,
+    mut log: EventWriter<BotLog>,
+) {
+    for event in events.read() {
+        let BotCommand { entity, command, .. } = event;
+        if !matches!(command, CommandType::Set { .. } | CommandType::Get { .. }) {
+            continue;
+        }
+        let Ok((killaura, auto_eat, auto_armor, safety, farming)) = query.get_mut(*entity) else {
+            continue;
+        };
+        let mut settings = BotSettings {
+            killaura: killaura.map(Mut::into_inner),
+            auto_eat: auto_eat.map(Mut::into_inner),
+            auto_armor: auto_armor.map(Mut::into_inner),
+            safety: safety.map(Mut::into_inner),
+            farming: farming.map(Mut::into_inner),
+        };
+        let lines = match command {
+            CommandType::Set { module, key, value } => match settings.set(module, key, value) {
+                Ok(line) => vec![line],
+                Err(e) => vec![format!("error: {e}")],
+            },
+            CommandType::Get { module, key } => settings.get(module.as_deref(), key.as_deref()),
+            _ => continue,
+        };
+        for line in lines {
+            log.send(event.reply(line));
+        }
+    }
+}
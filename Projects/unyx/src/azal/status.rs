@@ -0,0 +1,171 @@
+// Snapshots of every bot for the TUI status panel. They are built inside the
+// ECS each tick and sent over a channel, so the TUI never locks the world.
+
+use std::sync::mpsc::Sender;
+
+use azalea::{
+    app::{App, Plugin},
+    ecs::prelude::*,
+    entity::{
+        metadata::{Health, Player, PlayerAbsorption},
+        LocalEntity, LookDirection, Position,
+    },
+    inventory::Inventory,
+    prelude::*,
+    world::InstanceName,
+    GameProfileComponent, Hunger, Vec3,
+};
+
+use super::{
+    modules::{
+        auto_armor::AutoArmorSettings,
+        auto_eat::AutoEatSettings,
+        killaura::KillauraSettings,
+        safety::{SafetySettings, SafetyState},
+    },
+    tasks::TaskQueue,
+    inventory::{item_view, InventoryView, ItemView},
+    trackers::{experience::Experience, game_tick::*},
+};
+
+/// What the status panel shows for one bot
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotStatus {
+    pub name: String,
+    /// False once the bot dropped, the rest is what it was last seen with
+    pub online: bool,
+    pub health: f32,
+    pub absorption: f32,
+    pub food: u32,
+    pub saturation: f32,
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub dimension: String,
+    pub experience: Experience,
+    pub held_item: ItemView,
+    pub inventory: InventoryView,
+    pub modules: Vec<(&'static str, bool)>,
+    /// What the safety module is getting the bot away from
+    pub danger: Option<String>,
+    pub task: Option<String>,
+}
+
+pub struct StatusPlugin {
+    pub tx: Sender<BotStatus>,
+}
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(StatusChannel(self.tx.clone())).add_systems(
+            GameTick,
+            (Self::send_status, Self::send_offline).after(GameTickPlugin::handle_game_ticks),
+        );
+    }
+}
+
+#[derive(Resource)]
+struct StatusChannel(Sender<BotStatus>);
+
+/// The snapshot sent last, so only changes go over the channel
+#[derive(Component)]
+struct LastStatus(BotStatus);
+
+type StatusQuery<'a> = (
+    Entity,
+    &'a GameProfileComponent,
+    &'a Health,
+    Option<&'a PlayerAbsorption>,
+    &'a Hunger,
+    &'a Position,
+    &'a LookDirection,
+    &'a InstanceName,
+    Option<&'a Experience>,
+    &'a Inventory,
+    ModulesQuery<'a>,
+    Option<&'a TaskQueue>,
+    Option<&'a SafetyState>,
+    Option<&'a LastStatus>,
+);
+
+/// Settings of the modules with an `enabled` toggle
+type ModulesQuery<'a> = (
+    Option<&'a KillauraSettings>,
+    Option<&'a AutoEatSettings>,
+    Option<&'a AutoArmorSettings>,
+    Option<&'a SafetySettings>,
+);
+
+type StatusFilter = (With<Player>, With<LocalEntity>);
+
+impl StatusPlugin {
+    fn send_status(query: Query<StatusQuery, StatusFilter>, channel: Res<StatusChannel>, mut commands: Commands) {
+        for (
+            entity,
+            profile,
+            health,
+            absorption,
+            hunger,
+            position,
+            look,
+            instance_name,
+            experience,
+            inventory,
+            (killaura, auto_eat, auto_armor, safety),
+            tasks,
+            safety_state,
+            last,
+        ) in &query
+        {
+            let mut modules = Vec::new();
+            if let Some(killaura) = killaura {
+                modules.push(("killaura", killaura.enabled));
+            }
+            if let Some(auto_eat) = auto_eat {
+                modules.push(("auto_eat", auto_eat.enabled));
+            }
+            if let Some(auto_armor) = auto_armor {
+                modules.push(("auto_armor", auto_armor.enabled));
+            }
+            if let Some(safety) = safety {
+                modules.push(("safety", safety.enabled));
+            }
+            let status = BotStatus {
+                name: profile.name.clone(),
+                online: true,
+                health: **health,
+                absorption: absorption.map_or(0.0, |absorption| **absorption),
+                food: hunger.food,
+                saturation: hunger.saturation,
+                position: **position,
+                yaw: look.y_rot,
+                pitch: look.x_rot,
+                dimension: instance_name.path.clone(),
+                experience: experience.copied().unwrap_or_default(),
+                held_item: item_view(&inventory.held_item()),
+                inventory: InventoryView::new(inventory),
+                modules,
+                danger: safety_state.and_then(|state| state.danger).map(|danger| danger.to_string()),
+                task: tasks.and_then(TaskQueue::current).map(|task| format!("#{} {}", task.id, task.kind)),
+            };
+            if last.is_some_and(|last| last.0 == status) {
+                continue;
+            }
+            let _ = channel.0.send(status.clone());
+            commands.entity(entity).insert(LastStatus(status));
+        }
+    }
+
+    /// Tells the panel about bots that left the world since the last tick
+    fn send_offline(
+        mut query: Query<&mut LastStatus, Without<Position>>,
+        channel: Res<StatusChannel>,
+    ) {
+        for mut last in &mut query {
+            if last.0.online {
+                last.0.online = false;
+                let _ = channel.0.send(last.0.clone());
+            }
+        }
+    }
+}
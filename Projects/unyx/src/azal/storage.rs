@@ -0,0 +1,139 @@
+// Storage chests from the `[storage]` config. `deposit` tries them in order
+// and moves on when one is full, and bots running out of free slots queue a
+// high priority deposit on their own, putting their current task on hold
+// until the items are stored.
+
+use azalea::{
+    app::{App, Plugin},
+    ecs::prelude::*,
+    entity::{metadata::Player, LocalEntity},
+    inventory::{Inventory, Player as PlayerMenu},
+    prelude::*,
+    registry::Item,
+    BlockPos, GameProfileComponent,
+};
+
+use super::{
+    bridge::BotLog,
+    modules::auto_eat::FOOD_ITEMS,
+    tasks::{Priority, TaskKind, TaskQueue},
+    trackers::game_tick::*,
+};
+use crate::config::{SpotConfig, StorageConfig};
+
+/// Ticks before a bot that is still short on room queues another deposit
+const RETRY_TICKS: u128 = 20 * 60;
+/// Items ending like this are gear, `#gear` in the keep and deposit rules
+const GEAR_SUFFIXES: &[&str] = &[
+    "_pickaxe", "_axe", "_shovel", "_hoe", "_sword", "_helmet", "_chestplate", "_leggings", "_boots",
+];
+const GEAR_ITEMS: &[Item] = &[Item::Shield, Item::Bow, Item::Crossbow, Item::Trident, Item::Mace, Item::Arrow];
+
+/// Groups the keep and deposit rules take besides item names
+pub const ITEM_GROUPS: &[&str] = &["#gear", "#food"];
+
+/// The storage chests and what goes into them
+#[derive(Resource, Clone, Debug)]
+pub struct Storage {
+    /// Tried in this order, empty to use the nearest chest or barrel
+    pub chests: Vec<BlockPos>,
+    keep: Vec<String>,
+    deposit: Vec<String>,
+    min_free_slots: usize,
+}
+
+impl Storage {
+    /// Resolves the chests, waypoints have to be loaded already
+    pub fn new(config: &StorageConfig) -> Self {
+        Self {
+            chests: config.chests.iter().filter_map(SpotConfig::resolve).collect(),
+            keep: config.keep.clone(),
+            deposit: config.deposit.clone(),
+            min_free_slots: config.min_free_slots,
+        }
+    }
+
+    /// Whether `item` goes into the chests
+    pub fn deposits(&self, item: Item) -> bool {
+        let matches = |rules: &[String]| rules.iter().any(|rule| item_matches(rule, item));
+        !matches(&self.keep) && (self.deposit.is_empty() || matches(&self.deposit))
+    }
+}
+
+/// Whether `item` is named by `rule`, an item name or one of `ITEM_GROUPS`
+fn item_matches(rule: &str, item: Item) -> bool {
+    let name = item.to_string();
+    let name = name.trim_start_matches("minecraft:");
+    match rule {
+        "#gear" => GEAR_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) || GEAR_ITEMS.contains(&item),
+        "#food" => FOOD_ITEMS.contains_key(&item),
+        rule => rule.trim_start_matches("minecraft:").eq_ignore_ascii_case(name),
+    }
+}
+
+/// On a bot once it queued a deposit on its own
+#[derive(Component, Clone, Debug)]
+pub struct AutoDeposit {
+    retry_at: u128,
+}
+
+pub struct StoragePlugin {
+    pub config: StorageConfig,
+}
+
+impl Plugin for StoragePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Storage::new(&self.config))
+            .add_systems(GameTick, Self::handle_auto_deposit.after(GameTickPlugin::handle_game_ticks));
+    }
+}
+
+type QuerySet<'a> = (
+    Entity,
+    &'a GameTicks,
+    &'a Inventory,
+    &'a GameProfileComponent,
+    Option<&'a AutoDeposit>,
+    Option<&'a mut TaskQueue>,
+);
+
+type QueryFilter = (With<Player>, With<LocalEntity>);
+
+impl StoragePlugin {
+    /// Queues a deposit once fewer slots than `min_free_slots` are free,
+    /// only with storage chests set so bots don't fill random chests
+    fn handle_auto_deposit(
+        mut query: Query<QuerySet, QueryFilter>,
+        storage: Res<Storage>,
+        mut commands: Commands,
+        mut log_events: EventWriter<BotLog>,
+    ) {
+        if storage.min_free_slots == 0 || storage.chests.is_empty() {
+            return;
+        }
+        for (entity, game_ticks, inventory, profile, auto_deposit, tasks) in &mut query {
+            let now = game_ticks.0;
+            let Some(mut tasks) = tasks else {
+                continue;
+            };
+            if auto_deposit.is_some_and(|auto_deposit| now < auto_deposit.retry_at)
+                || tasks.contains(|kind| matches!(kind, TaskKind::Deposit))
+            {
+                continue;
+            }
+            let slots = inventory.inventory_menu.slots();
+            let items = PlayerMenu::INVENTORY_SLOTS.into_iter().map(|slot| &slots[slot]);
+            let free = items.clone().filter(|item| item.is_empty()).count();
+            // full of things it keeps, a deposit would not help
+            let depositable = items.into_iter().any(|item| !item.is_empty() && storage.deposits(item.kind()));
+            if free >= storage.min_free_slots || !depositable {
+                continue;
+            }
+            // the handler starts the runner on its next tick
+            let id = tasks.push(TaskKind::Deposit, Priority::High);
+            let line = format!("{free} free slot(s) left, queued task #{id}: deposit");
+            log_events.send(BotLog::new(&profile.name, line));
+            commands.entity(entity).insert(AutoDeposit { retry_at: now + RETRY_TICKS });
+        }
+    }
+}
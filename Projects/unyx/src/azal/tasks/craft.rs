@@ -0,0 +1,334 @@
+// `craft`: works out the whole recipe tree up front, from what is in the
+// inventory, so missing materials are reported before anything is crafted.
+// 2x2 recipes are crafted in the inventory, anything bigger sends the bot to
+// a crafting table, where the rest of the steps are done too.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use azalea::{
+    container::{ContainerClientExt, ContainerHandle},
+    inventory::{
+        item::MaxStackSizeExt,
+        operations::{PickupClick, QuickMoveClick},
+        Menu,
+    },
+    pathfinder::goals::ReachBlockPosGoal,
+    prelude::*,
+    registry::{Block, Item},
+    BlockPos,
+};
+
+use super::{TaskCtx, TaskError};
+use crate::azal::recipes::{self, Ingredient, Recipe};
+
+/// Crafting tables further away than this are not considered
+const TABLE_RADIUS: f64 = 64.0;
+/// Ticks to wait for the pathfinder before giving up on the table
+const PATH_TIMEOUT_TICKS: usize = 20 * 60;
+/// How long the server gets to open the crafting table
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Ticks the server gets to fill the result slot after the grid is filled
+const RESULT_TIMEOUT_TICKS: usize = 20;
+/// Most items one `craft` makes, a full inventory
+pub const MAX_COUNT: u32 = 64 * 36;
+/// Recipes nested deeper than this are not followed, exports have cycles
+/// like iron blocks and ingots
+const MAX_DEPTH: usize = 8;
+
+/// What a `craft` task makes
+#[derive(Clone, Debug)]
+pub struct CraftJob {
+    pub item: Item,
+    pub count: u32,
+}
+
+impl fmt::Display for CraftJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "craft {} {}", short_name(self.item), self.count)
+    }
+}
+
+fn short_name(item: Item) -> String {
+    item.to_string().trim_start_matches("minecraft:").to_string()
+}
+
+/// One recipe and how often to craft it
+#[derive(Clone, Debug)]
+struct Step {
+    recipe: &'static Recipe,
+    times: i32,
+}
+
+/// The steps to craft something, worked out against a copy of the inventory
+#[derive(Clone, Debug, Default)]
+struct Plan {
+    have: HashMap<Item, i32>,
+    steps: Vec<Step>,
+    /// Ingredient names and how many are missing
+    missing: Vec<(String, i32)>,
+}
+
+impl Plan {
+    fn new(menu: &Menu) -> Self {
+        let mut have = HashMap::new();
+        for item in menu.player_slots_range().filter_map(|slot| menu.slot(slot)).filter(|item| !item.is_empty()) {
+            *have.entry(item.kind()).or_default() += item.count();
+        }
+        Self { have, ..Default::default() }
+    }
+
+    /// Adds to an earlier entry for `name`, so attempts are compared by the
+    /// whole list and not just its length
+    fn add_missing(&mut self, name: String, count: i32) {
+        match self.missing.iter_mut().find(|(missing, _)| *missing == name) {
+            Some((_, missing)) => *missing = missing.saturating_add(count),
+            None => self.missing.push((name, count)),
+        }
+    }
+
+    /// Crafts `count` of `item` with the first recipe that has everything it
+    /// needs, or notes what the first recipe is missing
+    fn make(&mut self, item: Item, count: i32, depth: usize) {
+        let options = recipes::for_item(item);
+        if options.is_empty() || depth > MAX_DEPTH {
+            self.add_missing(short_name(item), count);
+            return;
+        }
+        let mut first = None;
+        for recipe in options {
+            let mut attempt = self.clone();
+            attempt.craft(recipe, count, depth);
+            if attempt.missing == self.missing {
+                *self = attempt;
+                return;
+            }
+            first.get_or_insert(attempt);
+        }
+        if let Some(first) = first {
+            *self = first;
+        }
+    }
+
+    fn craft(&mut self, recipe: &'static Recipe, count: i32, depth: usize) {
+        // deep trees multiply the counts, they saturate into a huge missing
+        // count instead of overflowing
+        let times = count / recipe.count + i32::from(count % recipe.count != 0);
+        for (ingredient, uses) in recipe.ingredients() {
+            self.obtain(ingredient, uses.saturating_mul(times), depth + 1);
+        }
+        self.steps.push(Step { recipe, times });
+        let leftover = times.saturating_mul(recipe.count).saturating_sub(count);
+        *self.have.entry(recipe.result).or_default() += leftover;
+    }
+
+    /// Takes `count` of any item fitting `ingredient` out of the inventory
+    /// and crafts whatever isn't there
+    fn obtain(&mut self, ingredient: &Ingredient, count: i32, depth: usize) {
+        let mut left = count;
+        for item in &ingredient.items {
+            let have = self.have.entry(*item).or_default();
+            let taken = (*have).min(left);
+            *have -= taken;
+            left -= taken;
+        }
+        if left == 0 {
+            return;
+        }
+        if let [item] = ingredient.items.as_slice() {
+            self.make(*item, left, depth);
+            return;
+        }
+        // any choice that can be crafted will do, like planks of any wood
+        for item in ingredient.items.iter().filter(|item| !recipes::for_item(**item).is_empty()) {
+            let mut attempt = self.clone();
+            attempt.make(*item, left, depth);
+            if attempt.missing == self.missing {
+                *self = attempt;
+                return;
+            }
+        }
+        self.add_missing(ingredient.name.clone(), left);
+    }
+}
+
+/// Crafts the job's item and everything it is made of
+pub async fn run(ctx: &mut TaskCtx, job: &CraftJob) -> Result<(), TaskError> {
+    let name = short_name(job.item);
+    if recipes::for_item(job.item).is_empty() {
+        return Err(TaskError::Failed(format!("no recipe for {name}, is the recipe export in place?")));
+    }
+    let count = i32::try_from(job.count).unwrap_or(i32::MAX);
+    let mut plan = Plan::new(&ctx.bot.menu());
+    plan.make(job.item, count, 0);
+    if !plan.missing.is_empty() {
+        let missing = plan.missing.iter().map(|(name, count)| format!("{count} {name}")).collect::<Vec<_>>();
+        return Err(TaskError::Failed(format!("missing {}", missing.join(", "))));
+    }
+    let before = item_count(&ctx.bot.menu(), job.item);
+
+    let (container, grid_width) = if plan.steps.iter().all(|step| step.recipe.fits_inventory()) {
+        let Some(inventory) = ctx.bot.open_inventory() else {
+            return Err(TaskError::Failed("a container is open".into()));
+        };
+        (inventory, 2)
+    } else {
+        (open_table(ctx).await?, 3)
+    };
+    for step in &plan.steps {
+        craft_step(ctx, &container, grid_width, step).await?;
+    }
+    drop(container);
+
+    let made = item_count(&ctx.bot.menu(), job.item) - before;
+    ctx.log(format!("crafted {made} {name}"));
+    Ok(())
+}
+
+/// Walks to a crafting table from the config, or the nearest one, and opens it
+async fn open_table(ctx: &mut TaskCtx) -> Result<ContainerHandle, TaskError> {
+    let Some(pos) = recipes::tables().first().copied().or_else(|| nearest_table(&ctx.bot)) else {
+        return Err(TaskError::Failed(format!("no crafting table set or within {TABLE_RADIUS} blocks")));
+    };
+    let chunk_storage = ctx.bot.world().read().chunks.clone();
+    ctx.bot.goto(ReachBlockPosGoal { pos, chunk_storage });
+    ctx.wait_for_path(PATH_TIMEOUT_TICKS).await?;
+
+    ctx.bot.look_at(pos.center());
+    match tokio::time::timeout(OPEN_TIMEOUT, ctx.bot.open_container_at(pos)).await {
+        Ok(Some(container)) if matches!(container.menu(), Some(Menu::Crafting { .. })) => Ok(container),
+        _ => Err(TaskError::Failed(format!("could not open the crafting table at {} {} {}", pos.x, pos.y, pos.z))),
+    }
+}
+
+fn nearest_table(bot: &Client) -> Option<BlockPos> {
+    let position = bot.position();
+    bot.world()
+        .read()
+        .find_blocks(position, &Block::CraftingTable.into())
+        .filter(|pos| pos.center().distance_to(&position) <= TABLE_RADIUS)
+        .min_by(|a, b| {
+            let a = a.center().distance_squared_to(&position);
+            let b = b.center().distance_squared_to(&position);
+            a.total_cmp(&b)
+        })
+}
+
+/// Fills the grid with as many crafts at once as the stacks allow, then
+/// shift-clicks the result, until the step is done
+async fn craft_step(
+    ctx: &mut TaskCtx,
+    container: &ContainerHandle,
+    grid_width: usize,
+    step: &Step,
+) -> Result<(), TaskError> {
+    let recipe = step.recipe;
+    let placement = recipe.placement(grid_width);
+    let mut left = step.times;
+    while left > 0 {
+        let Some(menu) = container.menu() else {
+            return Err(TaskError::Failed("the crafting grid closed".into()));
+        };
+        // leftovers of an earlier craft would change the recipe
+        let grid = 1..=grid_width * grid_width;
+        let leftovers = grid.filter(|slot| menu.slot(*slot).is_some_and(|item| !item.is_empty())).collect::<Vec<_>>();
+        if !leftovers.is_empty() {
+            for slot in leftovers {
+                container.click(QuickMoveClick::Left { slot: slot_id(slot)? });
+            }
+            ctx.tick().await?;
+            continue;
+        }
+        let mut slots = menu
+            .player_slots_range()
+            .filter_map(|slot| Some((slot, menu.slot(slot)?)))
+            .filter(|(_, item)| !item.is_empty())
+            .map(|(slot, item)| (slot, item.kind(), item.count()))
+            .collect::<Vec<_>>();
+        let count_of = |slots: &[(usize, Item, i32)], item: Item| {
+            slots.iter().filter(|(_, kind, _)| *kind == item).map(|(_, _, count)| count).sum::<i32>()
+        };
+
+        // every slot of an ingredient gets the same item, the one most
+        // crafts are left for. Ingredients like `#planks` and `oak_planks`
+        // can pick the same item, so its uses add up, and the ones with the
+        // fewest choices pick first
+        let mut ingredients = recipe.ingredients();
+        ingredients.sort_by_key(|(ingredient, _)| ingredient.items.len());
+        let mut chosen = Vec::new();
+        let mut uses_of: HashMap<Item, i32> = HashMap::new();
+        for (ingredient, uses) in ingredients {
+            let crafts = |item: &Item| count_of(&slots, *item) / (uses_of.get(item).copied().unwrap_or(0) + uses);
+            let Some(item) = ingredient.items.iter().copied().max_by_key(crafts) else {
+                continue;
+            };
+            *uses_of.entry(item).or_default() += uses;
+            chosen.push((ingredient, item));
+        }
+        let batch = uses_of
+            .iter()
+            .map(|(item, uses)| (count_of(&slots, *item) / uses).min(item.max_stack_size()))
+            .fold(left, i32::min);
+        if batch == 0 {
+            return Err(TaskError::Failed(format!("ran out of ingredients for {}", recipe.id)));
+        }
+
+        for (grid_slot, ingredient) in &placement {
+            let Some((_, item)) = chosen.iter().find(|(chosen, _)| chosen == ingredient) else {
+                continue;
+            };
+            let mut needed = batch;
+            while needed > 0 {
+                let Some(source) = slots.iter_mut().find(|(_, kind, count)| kind == item && *count > 0) else {
+                    break;
+                };
+                let (from, to) = (slot_id(source.0)?, slot_id(*grid_slot)?);
+                container.click(PickupClick::Left { slot: Some(from) });
+                if source.2 <= needed {
+                    container.click(PickupClick::Left { slot: Some(to) });
+                    needed -= source.2;
+                    source.2 = 0;
+                } else {
+                    for _ in 0..needed {
+                        container.click(PickupClick::Right { slot: Some(to) });
+                    }
+                    container.click(PickupClick::Left { slot: Some(from) });
+                    source.2 -= needed;
+                    needed = 0;
+                }
+            }
+        }
+
+        wait_for_result(ctx, container, recipe).await?;
+        container.click(QuickMoveClick::Left { slot: 0 });
+        // let the server move the results before looking at the grid again
+        ctx.tick().await?;
+        ctx.tick().await?;
+        left -= batch;
+    }
+    Ok(())
+}
+
+async fn wait_for_result(ctx: &mut TaskCtx, container: &ContainerHandle, recipe: &Recipe) -> Result<(), TaskError> {
+    for _ in 0..RESULT_TIMEOUT_TICKS {
+        ctx.tick().await?;
+        let result = container.menu().and_then(|menu| menu.slot(0).map(|item| item.kind()));
+        if result == Some(recipe.result) {
+            return Ok(());
+        }
+    }
+    // closing the grid puts the ingredients back into the inventory
+    Err(TaskError::Failed(format!("the server did not accept {}", recipe.id)))
+}
+
+fn slot_id(slot: usize) -> Result<u16, TaskError> {
+    u16::try_from(slot).map_err(|_| TaskError::Failed(format!("no slot {slot}")))
+}
+
+/// How many of `item` are in the player part of `menu`
+fn item_count(menu: &Menu, item: Item) -> i32 {
+    menu.player_slots_range()
+        .filter_map(|slot| menu.slot(slot))
+        .filter(|stack| stack.kind() == item)
+        .map(|stack| stack.count())
+        .sum()
+}
@@ -0,0 +1,35 @@
+use azalea::{pathfinder::goals::RadiusGoal, prelude::*, Vec3};
+
+use super::{TaskCtx, TaskError};
+use crate::azal::modules::killaura::current_target;
+
+/// Ticks without a hostile mob around before the bot goes back to its work
+const CALM_TICKS: usize = 20 * 2;
+
+/// Walks up to whatever killaura is after so it can hit it, finishes once
+/// nothing is left nearby
+pub async fn run(ctx: &mut TaskCtx) -> Result<(), TaskError> {
+    let mut calm_ticks = 0;
+    let mut last_goal: Option<Vec3> = None;
+    loop {
+        match current_target(&ctx.bot) {
+            Some((target, attack_range)) => {
+                calm_ticks = 0;
+                let moved = last_goal.is_none_or(|goal| goal.distance_to(&target.position) > 1.0);
+                if target.distance >= attack_range && moved {
+                    let radius = (attack_range - 1.0).max(1.0) as f32;
+                    ctx.bot.goto_without_mining(RadiusGoal { pos: target.position, radius });
+                    last_goal = Some(target.position);
+                }
+            }
+            None => {
+                calm_ticks += 1;
+                if calm_ticks >= CALM_TICKS {
+                    ctx.bot.stop_pathfinding();
+                    return Ok(());
+                }
+            }
+        }
+        ctx.tick().await?;
+    }
+}
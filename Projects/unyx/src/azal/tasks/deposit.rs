@@ -0,0 +1,151 @@
+// `deposit`: empties the inventory into the storage chests of the config,
+// following the `[storage]` rules for what goes and what stays. Without any
+// chests set it uses the nearest chest or barrel. A full chest moves on to the
+// next one, and whatever is left over at the end is reported.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use azalea::{
+    blocks::BlockStates,
+    container::ContainerClientExt,
+    inventory::{operations::QuickMoveClick, Menu},
+    pathfinder::goals::ReachBlockPosGoal,
+    prelude::*,
+    registry::{Block, Item},
+    BlockPos,
+};
+
+use super::{TaskCtx, TaskError};
+use crate::azal::storage::Storage;
+
+/// Containers further away than this are not considered
+const CONTAINER_RADIUS: f64 = 32.0;
+/// Ticks to wait for the pathfinder before giving up on the container
+const PATH_TIMEOUT_TICKS: usize = 20 * 30;
+/// How long the server gets to open the container
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Puts everything the `[storage]` rules allow into the storage chests, one
+/// after another until it is all stored, or the nearest chest or barrel
+pub async fn run(ctx: &mut TaskCtx) -> Result<(), TaskError> {
+    store(ctx, &HashMap::new()).await
+}
+
+/// Like `run`, but holds back up to `keep` of some items, whole stacks at a
+/// time
+pub async fn store(ctx: &mut TaskCtx, keep: &HashMap<Item, i32>) -> Result<(), TaskError> {
+    let storage = ctx.bot.resource::<Storage>();
+    if deposited_count(&storage, &ctx.bot.menu(), keep) == 0 {
+        ctx.log("nothing to deposit");
+        return Ok(());
+    }
+    let chests = if storage.chests.is_empty() {
+        let Some(pos) = nearest_container(&ctx.bot) else {
+            let reason = format!("no storage chests set and no chest or barrel within {CONTAINER_RADIUS} blocks");
+            return Err(TaskError::Failed(reason));
+        };
+        vec![pos]
+    } else {
+        storage.chests.clone()
+    };
+
+    let at = |pos: BlockPos| format!("{} {} {}", pos.x, pos.y, pos.z);
+    let mut full = Vec::new();
+    let mut failed = Vec::new();
+    for pos in chests {
+        match deposit_at(ctx, &storage, keep, pos).await {
+            Ok(0) => return Ok(()),
+            Ok(_) => {
+                ctx.log(format!("the chest at {} is full", at(pos)));
+                full.push(at(pos));
+            }
+            Err(TaskError::Failed(reason)) => {
+                ctx.log(format!("skipping the chest at {}: {reason}", at(pos)));
+                failed.push(format!("{} ({reason})", at(pos)));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let left = deposited_count(&storage, &ctx.bot.menu(), keep);
+    let mut problems = Vec::new();
+    if !full.is_empty() {
+        problems.push(format!("full: {}", full.join(", ")));
+    }
+    if !failed.is_empty() {
+        problems.push(format!("unreachable: {}", failed.join(", ")));
+    }
+    Err(TaskError::Failed(format!("{left} item(s) left over, {}", problems.join("; "))))
+}
+
+/// Empties into the container at `pos`, returning how many items didn't fit
+async fn deposit_at(
+    ctx: &mut TaskCtx,
+    storage: &Storage,
+    keep: &HashMap<Item, i32>,
+    pos: BlockPos,
+) -> Result<i32, TaskError> {
+    let chunk_storage = ctx.bot.world().read().chunks.clone();
+    ctx.bot.goto(ReachBlockPosGoal { pos, chunk_storage });
+    ctx.wait_for_path(PATH_TIMEOUT_TICKS).await?;
+
+    ctx.bot.look_at(pos.center());
+    let Ok(Some(container)) = tokio::time::timeout(OPEN_TIMEOUT, ctx.bot.open_container_at(pos)).await else {
+        return Err(TaskError::Failed("could not open it".into()));
+    };
+    let Some(menu) = container.menu() else {
+        return Err(TaskError::Failed("it closed right away".into()));
+    };
+
+    let slots = deposited_slots(storage, &menu, keep);
+    let before = slots.iter().map(|(_, count)| count).sum::<i32>();
+    for (slot, _) in slots {
+        let Ok(slot) = u16::try_from(slot) else {
+            continue;
+        };
+        container.click(QuickMoveClick::Left { slot });
+    }
+    // let the server apply the clicks before the handle closes the container
+    ctx.tick().await?;
+    let after = container.menu().map(|menu| deposited_count(storage, &menu, keep)).unwrap_or(before);
+    ctx.log(format!("deposited {} item(s) at {} {} {}", before - after, pos.x, pos.y, pos.z));
+    Ok(after)
+}
+
+fn nearest_container(bot: &Client) -> Option<BlockPos> {
+    let position = bot.position();
+    let containers = BlockStates::from(HashSet::from([Block::Chest, Block::TrappedChest, Block::Barrel]));
+    bot.world()
+        .read()
+        .find_blocks(position, &containers)
+        .filter(|pos| pos.center().distance_to(&position) <= CONTAINER_RADIUS)
+        .min_by(|a, b| {
+            let a = a.center().distance_squared_to(&position);
+            let b = b.center().distance_squared_to(&position);
+            a.total_cmp(&b)
+        })
+}
+
+/// Slots in the player part of `menu` that should end up in the container
+/// and how many items each holds
+fn deposited_slots(storage: &Storage, menu: &Menu, keep: &HashMap<Item, i32>) -> Vec<(usize, i32)> {
+    let mut keep = keep.clone();
+    menu.player_slots_range()
+        .filter_map(|slot| Some((slot, menu.slot(slot)?)))
+        .filter(|(_, item)| !item.is_empty() && storage.deposits(item.kind()))
+        .filter(|(_, item)| match keep.get_mut(&item.kind()) {
+            Some(left) if *left > 0 => {
+                *left -= item.count();
+                false
+            }
+            _ => true,
+        })
+        .map(|(slot, item)| (slot, item.count()))
+        .collect()
+}
+
+fn deposited_count(storage: &Storage, menu: &Menu, keep: &HashMap<Item, i32>) -> i32 {
+    deposited_slots(storage, menu, keep).iter().map(|(_, count)| count).sum()
+}
@@ -0,0 +1,284 @@
+// `farm`: keeps the crops between two corners going. Every pass breaks
+// whatever is ripe, picks up the drops and replants from the inventory,
+// bonemeals young crops when asked to and stores the harvest. Then it waits
+// `farming.interval` game ticks and goes again, until it is cancelled.
+
+use std::{collections::HashMap, fmt};
+
+use azalea::{
+    inventory::{
+        operations::{ClickOperation, SwapClick},
+        ContainerClickEvent, Inventory, SetSelectedHotbarSlotEvent,
+    },
+    pathfinder::goals::ReachBlockPosGoal,
+    prelude::*,
+    registry::{Block, Item},
+    BlockPos, Vec3,
+};
+
+use super::{deposit, mine, TaskCtx, TaskError, TaskKind};
+use crate::azal::{
+    modules::farming::{Crop, FarmingSettings},
+    trackers::game_tick::GameTicks,
+};
+
+/// Widest farm `farm` takes along x and z
+pub const MAX_WIDTH: u32 = 128;
+/// Most layers a farm may span
+pub const MAX_HEIGHT: u32 = 32;
+/// Ticks to wait for the pathfinder before skipping a crop
+const PATH_TIMEOUT_TICKS: usize = 20 * 30;
+/// Ticks to wait for a harvested crop to disappear
+const BREAK_TIMEOUT_TICKS: usize = 20 * 2;
+/// Ticks the server gets to show a replanted or bonemealed crop
+const PLACE_TIMEOUT_TICKS: usize = 10;
+/// Bone meal spent on one crop at most
+const MAX_BONEMEAL: usize = 8;
+
+/// The farm's corners and how much it brought in
+#[derive(Clone, Debug)]
+pub struct FarmJob {
+    /// Lowest corner
+    pub from: BlockPos,
+    /// Highest corner
+    pub to: BlockPos,
+    /// Kept in the task so a preempted job keeps counting
+    pub harvested: usize,
+}
+
+impl FarmJob {
+    pub fn new(a: BlockPos, b: BlockPos) -> Self {
+        let from = BlockPos::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z));
+        let to = BlockPos::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z));
+        Self { from, to, harvested: 0 }
+    }
+
+    fn blocks(&self) -> impl Iterator<Item = BlockPos> + '_ {
+        (self.from.x..=self.to.x).flat_map(move |x| {
+            (self.from.y..=self.to.y).flat_map(move |y| (self.from.z..=self.to.z).map(move |z| BlockPos::new(x, y, z)))
+        })
+    }
+}
+
+impl fmt::Display for FarmJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (from, to) = (self.from, self.to);
+        write!(f, "farm {} {} {} {} {} {} ", from.x, from.y, from.z, to.x, to.y, to.z)?;
+        write!(f, "({} harvested)", self.harvested)
+    }
+}
+
+/// Goes over the farm again and again until the task is cancelled
+pub async fn run(ctx: &mut TaskCtx, job: &FarmJob) -> Result<(), TaskError> {
+    let mut harvested = job.harvested;
+    loop {
+        let settings = ctx.bot.get_component::<FarmingSettings>().unwrap_or_default();
+        let count = pass(ctx, job, &settings, &mut harvested).await?;
+        ctx.log(format!("harvested {count} crop(s), next pass in {} ticks", settings.interval));
+
+        if settings.deposit && count > 0 {
+            // seeds and bone meal stay for the next pass
+            let keep_seeds = i32::try_from(settings.keep_seeds).unwrap_or(i32::MAX);
+            let mut keep = Crop::ALL.map(|crop| (crop.seed(), keep_seeds)).into_iter().collect::<HashMap<_, _>>();
+            keep.insert(Item::BoneMeal, i32::MAX);
+            match deposit::store(ctx, &keep).await {
+                Ok(()) => {}
+                Err(TaskError::Failed(reason)) => ctx.log(format!("could not store the harvest: {reason}")),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let game_ticks = |ctx: &TaskCtx| ctx.bot.map_get_component::<GameTicks, _>(|ticks| ticks.map(|ticks| ticks.0));
+        let next = game_ticks(ctx).unwrap_or_default() + u128::from(settings.interval);
+        while game_ticks(ctx).is_none_or(|now| now < next) {
+            ctx.tick().await?;
+        }
+    }
+}
+
+/// Tends every ripe crop once, nearest first, returning how many were
+/// harvested
+async fn pass(
+    ctx: &mut TaskCtx,
+    job: &FarmJob,
+    settings: &FarmingSettings,
+    harvested: &mut usize,
+) -> Result<usize, TaskError> {
+    let mut crops = {
+        let world = ctx.bot.world();
+        let world = world.read();
+        job.blocks()
+            .filter(|pos| {
+                let Some(state) = world.get_block_state(pos) else {
+                    return false;
+                };
+                Crop::of(state).is_some_and(|crop| {
+                    crop.is_mature(state) || (settings.bonemeal && crop.takes_bonemeal())
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut count = 0;
+    while !crops.is_empty() {
+        let position = ctx.bot.position();
+        let nearest = (0..crops.len())
+            .min_by(|a, b| {
+                let a = crops[*a].center().distance_squared_to(&position);
+                let b = crops[*b].center().distance_squared_to(&position);
+                a.total_cmp(&b)
+            })
+            .unwrap_or_default();
+        let pos = crops.swap_remove(nearest);
+
+        match tend(ctx, pos, settings).await {
+            Ok(true) => {
+                count += 1;
+                *harvested += 1;
+                let harvested = *harvested;
+                ctx.update(|kind| {
+                    if let TaskKind::Farm(job) = kind {
+                        job.harvested = harvested;
+                    }
+                });
+            }
+            Ok(false) => {}
+            Err(TaskError::Failed(reason)) => {
+                ctx.log(format!("skipped the crop at {} {} {}: {reason}", pos.x, pos.y, pos.z));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(count)
+}
+
+/// Harvests and replants one crop, bonemealing it first if it is young,
+/// returns whether it was harvested
+async fn tend(ctx: &mut TaskCtx, pos: BlockPos, settings: &FarmingSettings) -> Result<bool, TaskError> {
+    let chunk_storage = ctx.bot.world().read().chunks.clone();
+    ctx.bot.goto(ReachBlockPosGoal { pos, chunk_storage });
+    ctx.wait_for_path(PATH_TIMEOUT_TICKS).await?;
+
+    let Some(state) = ctx.bot.world().read().get_block_state(&pos) else {
+        return Err(TaskError::Failed("chunk unloaded".into()));
+    };
+    let Some(crop) = Crop::of(state) else {
+        return Err(TaskError::Failed("the crop is gone".into()));
+    };
+    if !crop.is_mature(state) && !(settings.bonemeal && crop.takes_bonemeal() && bonemeal(ctx, pos, crop).await?) {
+        return Ok(false);
+    }
+
+    ctx.bot.look_at(pos.center());
+    ctx.bot.mine(pos).await;
+    let mut broken = false;
+    for _ in 0..BREAK_TIMEOUT_TICKS {
+        if ctx.bot.world().read().get_block_state(&pos).is_none_or(|state| Crop::of(state) != Some(crop)) {
+            broken = true;
+            break;
+        }
+        ctx.tick().await?;
+    }
+    if !broken {
+        return Err(TaskError::Failed("it did not break".into()));
+    }
+    mine::collect_drops(ctx, pos).await?;
+
+    // the harvest counts even when the crop can't be put back
+    if let Err(TaskError::Failed(reason)) = replant(ctx, pos, crop).await {
+        ctx.log(format!("could not replant at {} {} {}: {reason}", pos.x, pos.y, pos.z));
+    }
+    ctx.check()?;
+    Ok(true)
+}
+
+async fn replant(ctx: &mut TaskCtx, pos: BlockPos, crop: Crop) -> Result<(), TaskError> {
+    let soil = pos.down(1);
+    if ctx.bot.world().read().get_block_state(&soil).is_none_or(|state| Block::from(state) != crop.soil()) {
+        return Err(TaskError::Failed("the soil below is gone".into()));
+    }
+    if !hold(&ctx.bot, crop.seed()) {
+        return Err(TaskError::Failed(format!("no {} left", crop.seed().to_string().trim_start_matches("minecraft:"))));
+    }
+    // the hotbar change has to reach the server before the click
+    ctx.tick().await?;
+    ctx.bot.look_at(soil.center() + Vec3::new(0.0, 0.5, 0.0));
+    ctx.bot.block_interact(soil);
+    for _ in 0..PLACE_TIMEOUT_TICKS {
+        ctx.tick().await?;
+        if ctx.bot.world().read().get_block_state(&pos).and_then(Crop::of) == Some(crop) {
+            return Ok(());
+        }
+    }
+    Err(TaskError::Failed("the seed was not planted".into()))
+}
+
+/// Uses bone meal until the crop is ripe, false when it ran out first
+async fn bonemeal(ctx: &mut TaskCtx, pos: BlockPos, crop: Crop) -> Result<bool, TaskError> {
+    for _ in 0..MAX_BONEMEAL {
+        if !hold(&ctx.bot, Item::BoneMeal) {
+            return Ok(false);
+        }
+        ctx.tick().await?;
+        let before = ctx.bot.world().read().get_block_state(&pos);
+        ctx.bot.look_at(pos.center());
+        ctx.bot.block_interact(pos);
+        for _ in 0..PLACE_TIMEOUT_TICKS {
+            ctx.tick().await?;
+            if ctx.bot.world().read().get_block_state(&pos) != before {
+                break;
+            }
+        }
+        match ctx.bot.world().read().get_block_state(&pos) {
+            Some(state) if Crop::of(state) == Some(crop) => {
+                if crop.is_mature(state) {
+                    return Ok(true);
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+    Ok(false)
+}
+
+/// Puts `item` into the main hand from the hotbar or the rest of the
+/// inventory, false when there is none
+fn hold(bot: &Client, item: Item) -> bool {
+    let mut ecs = bot.ecs.lock();
+    let (slot, selected, hotbar, window_id, selected_hotbar_slot) = {
+        let inventory = bot.query::<&Inventory>(&mut ecs);
+        let menu = &inventory.inventory_menu;
+        let hotbar = menu.hotbar_slots_range();
+        let selected = *hotbar.start() + inventory.selected_hotbar_slot as usize;
+        let holds = |slot: usize| menu.slot(slot).is_some_and(|stack| stack.kind() == item);
+        // the hand first, then the hotbar, so we swap as little as possible
+        let mut slots = [selected].into_iter().chain(hotbar.clone()).chain(menu.player_slots_range());
+        let slot = slots.find(|slot| holds(*slot));
+        (slot, selected, hotbar, inventory.id, inventory.selected_hotbar_slot)
+    };
+    let Some(slot) = slot else {
+        return false;
+    };
+    if slot == selected {
+        return true;
+    }
+
+    let entity = bot.entity;
+    if hotbar.contains(&slot) {
+        let slot = (slot - *hotbar.start()) as u8;
+        ecs.send_event(SetSelectedHotbarSlotEvent { entity, slot });
+        true
+    } else if window_id == 0
+        && let Ok(source_slot) = u16::try_from(slot)
+    {
+        // slot numbers only line up with the player menu when no container is open
+        ecs.send_event(ContainerClickEvent {
+            entity,
+            window_id,
+            operation: ClickOperation::Swap(SwapClick { source_slot, target_slot: selected_hotbar_slot }),
+        });
+        true
+    } else {
+        false
+    }
+}
@@ -0,0 +1,166 @@
+// `follow`: keeps re-pathing after a player as they move. The bot walks once
+// the player is further than the job's max distance and stops within its
+// distance. While following, the bot carries an `Escort` so killaura only
+// fights what comes close to the player instead of wandering off.
+
+use std::fmt;
+
+use azalea::{
+    ecs::prelude::*,
+    entity::{metadata::Player, LocalEntity, Position},
+    pathfinder::goals::RadiusGoal,
+    prelude::*,
+    world::InstanceName,
+    GameProfileComponent, TabList, Vec3,
+};
+
+use super::{TaskCtx, TaskError};
+
+/// How close the bot stays when `follow` is given no distance
+pub const DEFAULT_DISTANCE: f32 = 3.0;
+/// Largest distance `follow` takes, further than that players get out of view
+pub const MAX_DISTANCE: f32 = 32.0;
+/// How much further than its distance the player may get before the bot moves
+const SLACK: f64 = 2.0;
+/// A player moving this far in one tick was teleported
+const TELEPORT_DISTANCE: f64 = 16.0;
+/// Ticks a followed player may be out of sight before the task gives up
+const LOST_TIMEOUT_TICKS: usize = 20 * 10;
+
+/// Who a `follow` task is after and how close it stays
+#[derive(Clone, Debug)]
+pub struct FollowJob {
+    pub player: String,
+    pub distance: f32,
+}
+
+impl FollowJob {
+    /// How far the player may get before the bot walks after them
+    fn max_distance(&self) -> f64 {
+        f64::from(self.distance) + SLACK
+    }
+}
+
+impl fmt::Display for FollowJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "follow {}", self.player)?;
+        if self.distance != DEFAULT_DISTANCE {
+            write!(f, " {}", self.distance)?;
+        }
+        Ok(())
+    }
+}
+
+/// On a bot while it follows a player, killaura only fights near them
+#[derive(Component, Clone, Debug)]
+pub struct Escort {
+    pub player: String,
+    /// Where the player was last seen
+    pub position: Vec3,
+}
+
+/// Keeps walking after the player until the task is cancelled, the player
+/// leaves the game or the bot's world, or is out of sight for too long
+pub async fn run(ctx: &mut TaskCtx, job: &FollowJob) -> Result<(), TaskError> {
+    let result = follow(ctx, job).await;
+    // a fight in between keeps the escort, the task resumes right after
+    if !(matches!(result, Err(TaskError::Interrupted)) && ctx.is_preempted()) {
+        ctx.bot.ecs.lock().entity_mut(ctx.bot.entity).remove::<Escort>();
+    }
+    result
+}
+
+async fn follow(ctx: &mut TaskCtx, job: &FollowJob) -> Result<(), TaskError> {
+    let player = job.player.as_str();
+    let mut lost_ticks = 0;
+    let mut last_goal: Option<Vec3> = None;
+    let mut last_seen: Option<Vec3> = None;
+    let mut world = ctx.bot.get_component::<InstanceName>();
+    loop {
+        // the bot went through a portal or respawned in another dimension
+        let now = ctx.bot.get_component::<InstanceName>();
+        if now != world {
+            if let Some(name) = &now {
+                ctx.log(format!("now in {}, looking for {player} there", **name));
+            }
+            world = now;
+            last_goal = None;
+            last_seen = None;
+        }
+        if !is_online(&ctx.bot, player) {
+            return Err(TaskError::Failed(format!("{player} left the game")));
+        }
+
+        match find_player(&ctx.bot, player) {
+            Some(position) => {
+                lost_ticks = 0;
+                if let Some(seen) = last_seen
+                    && seen.distance_to(&position) > TELEPORT_DISTANCE
+                {
+                    ctx.log(format!("{player} teleported {:.0} blocks away", seen.distance_to(&position)));
+                    last_goal = None;
+                }
+                last_seen = Some(position);
+                ctx.bot
+                    .ecs
+                    .lock()
+                    .entity_mut(ctx.bot.entity)
+                    .insert(Escort { player: player.to_string(), position });
+
+                let far = ctx.bot.position().distance_to(&position) > job.max_distance();
+                // only re-path once the player actually moved away from the last goal
+                let moved = last_goal.is_none_or(|goal| goal.distance_to(&position) > 1.0);
+                if far && moved {
+                    ctx.bot.goto(RadiusGoal { pos: position, radius: job.distance });
+                    last_goal = Some(position);
+                }
+            }
+            None => {
+                if let Some(elsewhere) = other_world(&ctx.bot, player) {
+                    return Err(TaskError::Failed(format!("{player} went to {elsewhere}")));
+                }
+                // walking to where they were usually brings them back in sight
+                if lost_ticks == 0
+                    && let Some(seen) = last_seen
+                {
+                    ctx.bot.goto(RadiusGoal { pos: seen, radius: job.distance });
+                    last_goal = Some(seen);
+                }
+                lost_ticks += 1;
+                if lost_ticks > LOST_TIMEOUT_TICKS {
+                    return Err(TaskError::Failed(format!("lost sight of {player}")));
+                }
+            }
+        }
+        ctx.tick().await?;
+    }
+}
+
+/// Whether `name` is in the tab list, true when the bot has none yet
+fn is_online(bot: &Client, name: &str) -> bool {
+    bot.get_component::<TabList>()
+        .is_none_or(|tab_list| tab_list.values().any(|info| info.profile.name.eq_ignore_ascii_case(name)))
+}
+
+/// Position of a player the bot can see, in the bot's own world
+fn find_player(bot: &Client, name: &str) -> Option<Vec3> {
+    let instance_name = bot.get_component::<InstanceName>()?;
+    let entity = bot.entity_by::<(With<Player>, Without<LocalEntity>), (&GameProfileComponent, &InstanceName)>(
+        |(profile, instance): &(&GameProfileComponent, &InstanceName)| {
+            profile.name.eq_ignore_ascii_case(name) && **instance == instance_name
+        },
+    )?;
+    bot.get_entity_component::<Position>(entity).map(|position| *position)
+}
+
+/// The world a player is in when another bot of the swarm sees them in a
+/// different one than this bot
+fn other_world(bot: &Client, name: &str) -> Option<String> {
+    let instance_name = bot.get_component::<InstanceName>()?;
+    let entity = bot.entity_by::<(With<Player>, Without<LocalEntity>), (&GameProfileComponent, &InstanceName)>(
+        |(profile, instance): &(&GameProfileComponent, &InstanceName)| {
+            profile.name.eq_ignore_ascii_case(name) && **instance != instance_name
+        },
+    )?;
+    bot.get_entity_component::<InstanceName>(entity).map(|instance| instance.to_string())
+}
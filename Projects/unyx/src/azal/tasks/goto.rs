@@ -0,0 +1,18 @@
+use azalea::{
+    pathfinder::goals::{BlockPosGoal, XZGoal},
+    prelude::*,
+};
+
+use super::{TaskCtx, TaskError};
+use crate::azal::command::GotoTarget;
+
+/// Ticks to wait for the pathfinder before giving up on a goto
+const GOTO_TIMEOUT_TICKS: usize = 20 * 60 * 5;
+
+pub async fn run(ctx: &mut TaskCtx, target: &GotoTarget) -> Result<(), TaskError> {
+    match *target {
+        GotoTarget::Block(pos) => ctx.bot.goto(BlockPosGoal(pos)),
+        GotoTarget::Column { x, z } => ctx.bot.goto(XZGoal { x, z }),
+    }
+    ctx.wait_for_path(GOTO_TIMEOUT_TICKS).await
+}
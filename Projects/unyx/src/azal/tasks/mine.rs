@@ -0,0 +1,246 @@
+use std::fmt;
+
+use azalea::{
+    blocks::{BlockState, BlockStates},
+    ecs::prelude::*,
+    entity::{metadata::ItemItem, mining::get_mine_progress, FluidOnEyes, Physics, Position},
+    inventory::{
+        operations::{ClickOperation, SwapClick},
+        ContainerClickEvent, Inventory, SetSelectedHotbarSlotEvent,
+    },
+    pathfinder::goals::{BlockPosGoal, ReachBlockPosGoal},
+    prelude::*,
+    registry::Item,
+    world::{Instance, InstanceName},
+    BlockPos, Vec3,
+};
+
+use super::{TaskCtx, TaskError, TaskKind};
+
+/// Blocks further away than this are never picked
+const SEARCH_RADIUS: f64 = 64.0;
+/// Extra candidates kept around in case some blocks turn out unreachable
+const SPARE_CANDIDATES: usize = 16;
+/// Ticks to wait for the pathfinder before giving up on a block
+const PATH_TIMEOUT_TICKS: usize = 20 * 30;
+/// Ticks to wait for the broken block to disappear from the world
+const BREAK_TIMEOUT_TICKS: usize = 20 * 2;
+/// Ticks to spend walking to the drops of a block
+const PICKUP_TIMEOUT_TICKS: usize = 20 * 4;
+/// Item entities this close to a mined block are treated as its drops
+const DROP_RADIUS: f64 = 4.0;
+
+/// What a `mine` task is after and how far it got
+#[derive(Clone, Debug)]
+pub struct MineJob {
+    pub block_states: BlockStates,
+    pub label: String,
+    pub quantity: usize,
+    /// Kept in the task so a preempted job picks up where it stopped
+    pub mined: usize,
+}
+
+impl fmt::Display for MineJob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mine {} {} ({} done)", self.quantity, self.label, self.mined)
+    }
+}
+
+pub async fn run(ctx: &mut TaskCtx, job: &MineJob) -> Result<(), TaskError> {
+    let MineJob { block_states, label, quantity, mut mined } = job.clone();
+    let mut candidates = find_candidates(&ctx.bot, &block_states, quantity - mined + SPARE_CANDIDATES);
+    if candidates.is_empty() {
+        return Err(TaskError::Failed(format!("no reachable {label} within {SEARCH_RADIUS} blocks")));
+    }
+    ctx.log(format!("mining {} {label}, {} candidate(s) found", quantity - mined, candidates.len()));
+
+    while mined < quantity {
+        // always go for whatever is closest to where the bot is standing now
+        let position = ctx.bot.position();
+        let Some(nearest) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let a = a.center().distance_squared_to(&position);
+                let b = b.center().distance_squared_to(&position);
+                a.total_cmp(&b)
+            })
+            .map(|(idx, _)| idx)
+        else {
+            break;
+        };
+        let pos = candidates.swap_remove(nearest);
+
+        match mine_block(ctx, pos, &block_states).await {
+            Ok(()) => {
+                mined += 1;
+                ctx.update(|kind| {
+                    if let TaskKind::Mine(job) = kind {
+                        job.mined = mined;
+                    }
+                });
+                ctx.log(format!("mined {mined}/{quantity} {label} at {} {} {}", pos.x, pos.y, pos.z));
+            }
+            Err(TaskError::Failed(reason)) => {
+                ctx.log(format!("skipped {label} at {} {} {}: {reason}", pos.x, pos.y, pos.z));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    if mined < quantity {
+        return Err(TaskError::Failed(format!("ran out of reachable {label} after mining {mined}/{quantity}")));
+    }
+    Ok(())
+}
+
+/// Nearest matching blocks with at least one open face, closest first
+fn find_candidates(bot: &Client, block_states: &BlockStates, limit: usize) -> Vec<BlockPos> {
+    let position = bot.position();
+    let world = bot.world();
+    let world = world.read();
+    let mut blocks = world
+        .find_blocks(position, block_states)
+        .filter(|pos| pos.center().distance_to(&position) <= SEARCH_RADIUS)
+        .filter(|pos| is_exposed(&world, *pos))
+        .take(limit)
+        .collect::<Vec<_>>();
+    blocks.sort_by(|a, b| {
+        let a = a.center().distance_squared_to(&position);
+        let b = b.center().distance_squared_to(&position);
+        a.total_cmp(&b)
+    });
+    blocks
+}
+
+fn is_exposed(world: &Instance, pos: BlockPos) -> bool {
+    [
+        BlockPos::new(1, 0, 0),
+        BlockPos::new(-1, 0, 0),
+        BlockPos::new(0, 1, 0),
+        BlockPos::new(0, -1, 0),
+        BlockPos::new(0, 0, 1),
+        BlockPos::new(0, 0, -1),
+    ]
+    .into_iter()
+    .any(|offset| {
+        world
+            .get_block_state(&(pos + offset))
+            .is_some_and(|state| state.is_air())
+    })
+}
+
+/// Fails with the reason a single block was skipped, or bubbles up an
+/// interruption of the whole task
+async fn mine_block(ctx: &mut TaskCtx, pos: BlockPos, block_states: &BlockStates) -> Result<(), TaskError> {
+    let bot = ctx.bot.clone();
+    let chunk_storage = bot.world().read().chunks.clone();
+    bot.goto(ReachBlockPosGoal { pos, chunk_storage });
+    ctx.wait_for_path(PATH_TIMEOUT_TICKS).await?;
+
+    let Some(block_state) = bot.world().read().get_block_state(&pos) else {
+        return Err(TaskError::Failed("chunk unloaded".into()));
+    };
+    if !block_states.contains(&block_state) {
+        return Err(TaskError::Failed("block is already gone".into()));
+    }
+
+    equip_best_tool(&bot, block_state);
+    bot.look_at(pos.center());
+    bot.mine(pos).await;
+
+    let mut broken = false;
+    for _ in 0..BREAK_TIMEOUT_TICKS {
+        if bot.world().read().get_block_state(&pos).is_none_or(|state| !block_states.contains(&state)) {
+            broken = true;
+            break;
+        }
+        ctx.tick().await?;
+    }
+    if !broken {
+        return Err(TaskError::Failed("block did not break".into()));
+    }
+
+    collect_drops(ctx, pos).await
+}
+
+/// Walks over the item entities lying around a freshly mined block
+pub async fn collect_drops(ctx: &mut TaskCtx, pos: BlockPos) -> Result<(), TaskError> {
+    // give the server a moment to spawn the drops
+    for _ in 0..5 {
+        ctx.tick().await?;
+    }
+
+    let bot = ctx.bot.clone();
+    let center = pos.center();
+    let bot_instance_name = bot.component::<InstanceName>();
+    let drops: Vec<Vec3> = {
+        let mut ecs = bot.ecs.lock();
+        let mut query = ecs.query_filtered::<(&Position, &InstanceName), With<ItemItem>>();
+        query
+            .iter(&ecs)
+            .filter(|(_, instance_name)| **instance_name == bot_instance_name)
+            .map(|(position, _)| **position)
+            .filter(|position| position.distance_to(&center) <= DROP_RADIUS)
+            .collect()
+    };
+
+    for drop in drops {
+        bot.goto(BlockPosGoal(BlockPos::from(drop)));
+        match ctx.wait_for_path(PICKUP_TIMEOUT_TICKS).await {
+            // a drop we can't reach is not worth skipping the block over
+            Ok(()) | Err(TaskError::Failed(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Puts the fastest tool for `block` from anywhere in the inventory into the
+/// selected hotbar slot
+fn equip_best_tool(bot: &Client, block: BlockState) {
+    let mut ecs = bot.ecs.lock();
+    let (best_slot, selected, hotbar, window_id, selected_hotbar_slot) = {
+        let (inventory, physics, fluid_on_eyes) =
+            bot.query::<(&Inventory, &Physics, &FluidOnEyes)>(&mut ecs);
+        let menu = &inventory.inventory_menu;
+        let block = Box::<dyn azalea::blocks::Block>::from(block);
+        let hotbar = menu.hotbar_slots_range();
+        let selected = *hotbar.start() + inventory.selected_hotbar_slot as usize;
+
+        let speed_of = |slot: usize| {
+            let item = menu.slot(slot).map(|item| item.kind()).unwrap_or(Item::Air);
+            get_mine_progress(block.as_ref(), item, menu, fluid_on_eyes, physics)
+        };
+        // the held item wins ties, then the hotbar, so we swap as little as possible
+        let mut best_slot = selected;
+        let mut best_speed = speed_of(selected);
+        for slot in hotbar.clone().chain(menu.player_slots_range()) {
+            let speed = speed_of(slot);
+            if speed > best_speed {
+                best_slot = slot;
+                best_speed = speed;
+            }
+        }
+        (best_slot, selected, hotbar, inventory.id, inventory.selected_hotbar_slot)
+    };
+    if best_slot == selected {
+        return;
+    }
+
+    let entity = bot.entity;
+    if hotbar.contains(&best_slot) {
+        let slot = (best_slot - *hotbar.start()) as u8;
+        ecs.send_event(SetSelectedHotbarSlotEvent { entity, slot });
+    } else if window_id == 0 {
+        // slot numbers only line up with the player menu when no container is open
+        let Ok(source_slot) = u16::try_from(best_slot) else {
+            return;
+        };
+        ecs.send_event(ContainerClickEvent {
+            entity,
+            window_id,
+            operation: ClickOperation::Swap(SwapClick { source_slot, target_slot: selected_hotbar_slot }),
+        });
+    }
+}
@@ -0,0 +1,522 @@
+// Per-bot task queue. It lives as a component on the bot entity, so every
+// handler clone and every tick system sees the same queue, and it survives
+// reconnects since azalea keeps the entity around.
+
+pub mod craft;
+pub mod defend;
+pub mod deposit;
+pub mod farm;
+pub mod follow;
+pub mod goto;
+pub mod mine;
+pub mod retreat;
+
+use std::{fmt, str::FromStr};
+
+use azalea::{
+    ecs::prelude::*,
+    entity::{LocalEntity, Position},
+    pathfinder::{ExecutingPath, Pathfinder},
+    prelude::*,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use super::{
+    bridge::{BotCommand, BotLog, LogChannel},
+    command::{CommandType, GotoTarget, TaskCommand, TaskSelector},
+};
+use craft::CraftJob;
+use farm::FarmJob;
+use follow::FollowJob;
+use mine::MineJob;
+
+/// How many failed tasks the queue remembers the reason of
+const FAILURES_KEPT: usize = 16;
+
+/// Higher priorities interrupt lower ones, which resume once they are done
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(Priority::Low),
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TaskKind {
+    Goto(GotoTarget),
+    Mine(MineJob),
+    Follow(FollowJob),
+    Deposit,
+    Craft(CraftJob),
+    Farm(FarmJob),
+    /// Queued by killaura to fight back while the bot is busy
+    Defend,
+    /// Queued by the safety module to get out of danger
+    Retreat,
+}
+
+impl fmt::Display for TaskKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskKind::Goto(GotoTarget::Block(pos)) => write!(f, "goto {} {} {}", pos.x, pos.y, pos.z),
+            TaskKind::Goto(GotoTarget::Column { x, z }) => write!(f, "goto {x} {z}"),
+            TaskKind::Mine(job) => write!(f, "{job}"),
+            TaskKind::Follow(job) => write!(f, "{job}"),
+            TaskKind::Deposit => write!(f, "deposit"),
+            TaskKind::Craft(job) => write!(f, "{job}"),
+            TaskKind::Farm(job) => write!(f, "{job}"),
+            TaskKind::Defend => write!(f, "defend"),
+            TaskKind::Retreat => write!(f, "retreat"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Pending,
+    Running,
+    Paused,
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskStatus::Pending => write!(f, "pending"),
+            TaskStatus::Running => write!(f, "running"),
+            TaskStatus::Paused => write!(f, "paused"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Task {
+    pub id: u32,
+    pub kind: TaskKind,
+    pub priority: Priority,
+    pub status: TaskStatus,
+    /// Whether the task ran before, so a preempted task logs "resumed"
+    pub started: bool,
+    /// Player who queued it from chat, what the task logs is whispered back
+    pub from: Option<String>,
+}
+
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} [{}, {}] {}", self.id, self.status, self.priority, self.kind)
+    }
+}
+
+#[derive(Component, Debug, Default)]
+pub struct TaskQueue {
+    pub tasks: Vec<Task>,
+    next_id: u32,
+    /// Set while a runner is working through the queue
+    runner_active: bool,
+    /// Ids and reasons of the last tasks that failed, newest last, so a
+    /// script can tell a failed task from a finished one
+    failures: Vec<(u32, String)>,
+}
+
+impl TaskQueue {
+    /// Adds a task without starting the runner, for ECS systems that can't
+    /// spawn it themselves, `poll` picks it up on the next tick
+    pub fn push(&mut self, kind: TaskKind, priority: Priority) -> u32 {
+        self.push_from(kind, priority, None)
+    }
+
+    /// Like `push`, for a task queued by `from` in chat
+    pub fn push_from(&mut self, kind: TaskKind, priority: Priority, from: Option<String>) -> u32 {
+        self.next_id += 1;
+        self.tasks.push(Task {
+            id: self.next_id,
+            kind,
+            priority,
+            status: TaskStatus::Pending,
+            started: false,
+            from,
+        });
+        self.next_id
+    }
+
+    /// Highest priority pending task, the oldest one on ties
+    fn next_pending(&mut self) -> Option<&mut Task> {
+        self.tasks
+            .iter_mut()
+            .filter(|task| task.status == TaskStatus::Pending)
+            .max_by(|a, b| a.priority.cmp(&b.priority).then(b.id.cmp(&a.id)))
+    }
+
+    pub fn current(&self) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.status == TaskStatus::Running)
+    }
+
+    pub fn get_mut(&mut self, id: u32) -> Option<&mut Task> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    /// Why the task with `id` failed, if it did and is recent enough
+    pub fn failure(&self, id: u32) -> Option<&str> {
+        self.failures.iter().find(|(failed, _)| *failed == id).map(|(_, reason)| reason.as_str())
+    }
+
+    pub fn contains(&self, matches: impl Fn(&TaskKind) -> bool) -> bool {
+        self.tasks.iter().any(|task| matches(&task.kind))
+    }
+
+    fn selected(&mut self, selector: TaskSelector) -> impl Iterator<Item = &mut Task> {
+        self.tasks.iter_mut().filter(move |task| match selector {
+            TaskSelector::All => true,
+            TaskSelector::Id(id) => task.id == id,
+        })
+    }
+}
+
+/// Runs `f` on the task queue of `bot`, adding an empty queue first if needed
+pub fn with_tasks<R>(bot: &Client, f: impl FnOnce(&mut TaskQueue) -> R) -> R {
+    let mut ecs = bot.ecs.lock();
+    let mut entity = ecs.entity_mut(bot.entity);
+    if !entity.contains::<TaskQueue>() {
+        entity.insert(TaskQueue::default());
+    }
+    let mut tasks = entity.get_mut::<TaskQueue>().expect("task queue was just inserted");
+    f(&mut tasks)
+}
+
+/// Gives every bot an empty queue, so commands always have one to go in
+pub fn add_queues(query: Query<Entity, (With<LocalEntity>, Without<TaskQueue>)>, mut commands: Commands) {
+    for entity in &query {
+        commands.entity(entity).insert(TaskQueue::default());
+    }
+}
+
+/// Queues the tasks of `goto`, `mine`, `follow` and `deposit` and handles
+/// `tasks`, the handler starts the runner on its next tick
+pub fn handle_commands(
+    mut events: EventReader<BotCommand>,
+    mut query: Query<&mut TaskQueue>,
+    mut log: EventWriter<BotLog>,
+) {
+    for event in events.read() {
+        let BotCommand { entity, command, .. } = event;
+        let Ok(mut tasks) = query.get_mut(*entity) else {
+            continue;
+        };
+        let kind = match command {
+            CommandType::Goto(target) => TaskKind::Goto(target.clone()),
+            CommandType::Mine { blocks, quantity } => TaskKind::Mine(MineJob {
+                block_states: blocks.block_states(),
+                label: blocks.label.clone(),
+                quantity: *quantity,
+                mined: 0,
+            }),
+            CommandType::Follow { player, distance } => {
+                TaskKind::Follow(FollowJob { player: player.clone(), distance: *distance })
+            }
+            CommandType::Deposit => TaskKind::Deposit,
+            CommandType::Craft { item, count } => TaskKind::Craft(CraftJob { item: *item, count: *count }),
+            CommandType::Farm { from, to } => TaskKind::Farm(FarmJob::new(*from, *to)),
+            CommandType::Tasks(command) => {
+                for line in control(&mut tasks, command.clone()) {
+                    log.send(event.reply(line));
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let ahead = tasks.tasks.len();
+        let id = tasks.push_from(kind.clone(), Priority::Normal, event.from.clone());
+        let line = if ahead == 0 {
+            format!("queued task #{id}: {kind}")
+        } else {
+            format!("queued task #{id}: {kind}, {ahead} other task(s) in the queue")
+        };
+        log.send(event.reply(line));
+    }
+}
+
+/// Starts the runner for tasks that were pushed straight into the queue
+pub fn poll(bot: &Client, name: &str) {
+    let waiting = with_tasks(bot, |tasks| {
+        !tasks.runner_active && tasks.tasks.iter().any(|task| task.status == TaskStatus::Pending)
+    });
+    if waiting {
+        ensure_runner(bot, name);
+    }
+}
+
+/// Puts the running task back in the queue when the bot drops, so it starts
+/// over from a fresh path once the bot is back in the world
+pub fn suspend(bot: &Client) {
+    with_tasks(bot, |tasks| {
+        if let Some(task) = tasks.tasks.iter_mut().find(|task| task.status == TaskStatus::Running) {
+            task.status = TaskStatus::Pending;
+        }
+    });
+}
+
+/// How many tasks are still queued, e.g. after a reconnect
+pub fn queued(bot: &Client) -> usize {
+    with_tasks(bot, |tasks| tasks.tasks.len())
+}
+
+fn ensure_runner(bot: &Client, name: &str) {
+    let start = with_tasks(bot, |tasks| !std::mem::replace(&mut tasks.runner_active, true));
+    if start {
+        tokio::spawn(run_tasks(bot.clone(), name.to_string()));
+    }
+}
+
+async fn run_tasks(bot: Client, name: String) {
+    let log = bot.resource::<LogChannel>();
+    loop {
+        // pick and mark under one lock so a task queued right now is not lost
+        let next = with_tasks(&bot, |tasks| {
+            let Some(task) = tasks.next_pending() else {
+                tasks.runner_active = false;
+                return None;
+            };
+            let resumed = std::mem::replace(&mut task.started, true);
+            task.status = TaskStatus::Running;
+            Some((task.clone(), resumed))
+        });
+        let Some((task, resumed)) = next else {
+            break;
+        };
+
+        let mut ctx = TaskCtx::new(bot.clone(), name.clone(), log.clone(), &task);
+        let result = match ctx.tick().await {
+            Err(e) => Err(e),
+            Ok(()) => {
+                let verb = if resumed { "resumed" } else { "started" };
+                ctx.log(format!("{verb} task #{}: {}", task.id, task.kind));
+                match &task.kind {
+                    TaskKind::Goto(target) => goto::run(&mut ctx, target).await,
+                    TaskKind::Mine(job) => mine::run(&mut ctx, job).await,
+                    TaskKind::Follow(job) => follow::run(&mut ctx, job).await,
+                    TaskKind::Deposit => deposit::run(&mut ctx).await,
+                    TaskKind::Craft(job) => craft::run(&mut ctx, job).await,
+                    TaskKind::Farm(job) => farm::run(&mut ctx, job).await,
+                    TaskKind::Defend => defend::run(&mut ctx).await,
+                    TaskKind::Retreat => retreat::run(&mut ctx).await,
+                }
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                with_tasks(&bot, |tasks| tasks.tasks.retain(|t| t.id != task.id));
+                ctx.log(format!("finished task #{}: {}", task.id, task.kind));
+            }
+            Err(TaskError::Failed(reason)) => {
+                with_tasks(&bot, |tasks| {
+                    tasks.tasks.retain(|t| t.id != task.id);
+                    if tasks.failures.len() >= FAILURES_KEPT {
+                        tasks.failures.remove(0);
+                    }
+                    tasks.failures.push((task.id, reason.clone()));
+                });
+                ctx.log(format!("task #{} failed: {reason}", task.id));
+            }
+            Err(TaskError::Interrupted) => {
+                bot.stop_pathfinding();
+                // cancelled tasks are gone already and paused ones stay paused
+                let preempted = with_tasks(&bot, |tasks| match tasks.get_mut(task.id) {
+                    Some(t) if t.status == TaskStatus::Running => {
+                        t.status = TaskStatus::Pending;
+                        true
+                    }
+                    _ => false,
+                });
+                if preempted {
+                    ctx.log(format!("put task #{} on hold for a more important one", task.id));
+                }
+            }
+        }
+    }
+}
+
+/// Why a task stopped before finishing
+#[derive(Debug)]
+pub enum TaskError {
+    /// Cancelled, paused or preempted, the runner sorts out which
+    Interrupted,
+    Failed(String),
+}
+
+/// Handle a running task uses to wait for ticks and to notice it has to stop
+pub struct TaskCtx {
+    pub bot: Client,
+    pub name: String,
+    log_channel: LogChannel,
+    /// Player to whisper the log lines to, see `Task::from`
+    from: Option<String>,
+    id: u32,
+    ticks: Receiver<()>,
+}
+
+impl TaskCtx {
+    fn new(bot: Client, name: String, log_channel: LogChannel, task: &Task) -> Self {
+        let ticks = bot.get_tick_broadcaster();
+        let from = task.from.clone();
+        Self { bot, name, log_channel, from, id: task.id, ticks }
+    }
+
+    /// A line in the bot log, also whispered to the player who queued the task
+    pub fn log(&self, msg: impl Into<String>) {
+        match &self.from {
+            // the bridge forwards it to the log channel as well
+            Some(player) => {
+                let whisper = Some((self.bot.entity, player.clone()));
+                self.bot.ecs.lock().send_event(BotLog { whisper, ..BotLog::new(&self.name, msg) });
+            }
+            None => self.log_channel.bot_log(&self.name, msg),
+        }
+    }
+
+    /// Changes the stored copy of the task, e.g. to keep progress around for
+    /// when the task resumes
+    pub fn update(&self, f: impl FnOnce(&mut TaskKind)) {
+        with_tasks(&self.bot, |tasks| {
+            if let Some(task) = tasks.get_mut(self.id) {
+                f(&mut task.kind);
+            }
+        });
+    }
+
+    /// Errors when the task was cancelled, paused or has to make room for a
+    /// higher priority task
+    pub fn check(&self) -> Result<(), TaskError> {
+        with_tasks(&self.bot, |tasks| {
+            // the queue entry, `tasks priority` may have changed it since the start
+            let Some(current) = tasks.tasks.iter().find(|task| task.id == self.id && task.status == TaskStatus::Running)
+            else {
+                return Err(TaskError::Interrupted);
+            };
+            let priority = current.priority;
+            let preempted =
+                tasks.tasks.iter().any(|task| task.status == TaskStatus::Pending && task.priority > priority);
+            if preempted { Err(TaskError::Interrupted) } else { Ok(()) }
+        })
+    }
+
+    /// Whether the task only stops to make room for a higher priority one
+    /// and resumes after it
+    pub fn is_preempted(&self) -> bool {
+        with_tasks(&self.bot, |tasks| {
+            tasks.tasks.iter().any(|task| task.id == self.id && task.status == TaskStatus::Running)
+        }) && self.check().is_err()
+    }
+
+    /// Waits for the next tick the bot spends in a world, then checks whether
+    /// the task should go on
+    pub async fn tick(&mut self) -> Result<(), TaskError> {
+        loop {
+            match self.ticks.recv().await {
+                Ok(()) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return Err(TaskError::Failed("the swarm stopped ticking".into())),
+            }
+            // while disconnected the bot has no position, just wait it out
+            if self.bot.get_component::<Position>().is_some() {
+                break;
+            }
+        }
+        self.check()
+    }
+
+    /// Waits until the pathfinder has reached its goal, stopping it on timeout
+    pub async fn wait_for_path(&mut self, max_ticks: usize) -> Result<(), TaskError> {
+        for _ in 0..max_ticks {
+            // the goto event is only picked up on the next update
+            self.tick().await?;
+            let has_goal = self.bot.map_get_component::<Pathfinder, _>(|pathfinder| {
+                pathfinder.is_some_and(|pathfinder| pathfinder.goal.is_some())
+            });
+            let executing = self.bot.map_get_component::<ExecutingPath, _>(|path| path.is_some());
+            if !has_goal && !executing {
+                return Ok(());
+            }
+        }
+        self.bot.stop_pathfinding();
+        Err(TaskError::Failed("no path".into()))
+    }
+}
+
+/// Handles the `tasks` command, returning the lines to log
+fn control(tasks: &mut TaskQueue, command: TaskCommand) -> Vec<String> {
+    match command {
+        TaskCommand::List => {
+            if tasks.tasks.is_empty() {
+                return vec!["no tasks".into()];
+            }
+            tasks.tasks.iter().map(Task::to_string).collect()
+        }
+        TaskCommand::Pause(selector) => {
+            let mut paused = Vec::new();
+            for task in tasks.selected(selector) {
+                if task.status != TaskStatus::Paused {
+                    task.status = TaskStatus::Paused;
+                    paused.push(task.id);
+                }
+            }
+            vec![log_ids("paused", selector, &paused)]
+        }
+        TaskCommand::Resume(selector) => {
+            // the handler starts the runner again on its next tick
+            let mut resumed = Vec::new();
+            for task in tasks.selected(selector) {
+                if task.status == TaskStatus::Paused {
+                    task.status = TaskStatus::Pending;
+                    resumed.push(task.id);
+                }
+            }
+            vec![log_ids("resumed", selector, &resumed)]
+        }
+        TaskCommand::Cancel(selector) => {
+            // a running task notices on its next tick and stops the pathfinder
+            let cancelled = tasks.selected(selector).map(|task| task.id).collect::<Vec<_>>();
+            tasks.tasks.retain(|task| !cancelled.contains(&task.id));
+            vec![log_ids("cancelled", selector, &cancelled)]
+        }
+        TaskCommand::Priority(id, priority) => match tasks.get_mut(id) {
+            Some(task) => {
+                task.priority = priority;
+                vec![format!("task #{id} now has {priority} priority")]
+            }
+            None => vec![format!("no task #{id}")],
+        },
+    }
+}
+
+fn log_ids(verb: &str, selector: TaskSelector, ids: &[u32]) -> String {
+    if ids.is_empty() {
+        return match selector {
+            TaskSelector::All => format!("no tasks to mark {verb}"),
+            TaskSelector::Id(id) => format!("task #{id} does not exist or is already {verb}"),
+        };
+    }
+    let ids = ids.iter().map(|id| format!("#{id}")).collect::<Vec<_>>().join(", ");
+    format!("{verb} task(s) {ids}")
+}
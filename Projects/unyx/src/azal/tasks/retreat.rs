@@ -0,0 +1,126 @@
+use azalea::{
+    blocks::BlockState,
+    ecs::prelude::*,
+    entity::{metadata::AbstractMonster, Dead, LocalEntity, Position},
+    pathfinder::goals::{BlockPosGoal, RadiusGoal},
+    physics::collision::BlockWithShape,
+    prelude::*,
+    registry::Block,
+    world::{Instance, InstanceName},
+    BlockPos, Vec3,
+};
+
+use super::{TaskCtx, TaskError};
+use crate::azal::{
+    modules::safety::{Danger, SafetySettings, SafetyState},
+    waypoints,
+};
+
+/// Ticks between re-planning the escape while the danger lasts
+const REPLAN_TICKS: usize = 10;
+/// How far to look for a spot out of lava, fire or water
+const SAFE_SEARCH_RADIUS: i32 = 8;
+/// Blocks to put between the bot and whatever it runs from
+const BACK_OFF_DISTANCE: f64 = 6.0;
+/// Monsters closer than this are backed off from at low health
+const MONSTER_RANGE: f64 = 16.0;
+
+/// Gets away from whatever the safety module sees, finishes once it sees
+/// nothing anymore
+pub async fn run(ctx: &mut TaskCtx) -> Result<(), TaskError> {
+    let mut ticks = 0;
+    let mut going_home = false;
+    loop {
+        let danger = ctx.bot.map_get_component::<SafetyState, _>(|state| state.and_then(|state| state.danger));
+        let Some(danger) = danger else {
+            ctx.bot.stop_pathfinding();
+            return Ok(());
+        };
+        if ticks % REPLAN_TICKS == 0 {
+            match danger {
+                Danger::Lava | Danger::Fire | Danger::Drowning => {
+                    if let Some(pos) = safe_spot(&ctx.bot) {
+                        ctx.bot.goto(BlockPosGoal(pos));
+                    }
+                }
+                Danger::Explosive(from) => back_off(&ctx.bot, from),
+                // nothing to steer mid-air, just don't walk anywhere after landing
+                Danger::Falling => ctx.bot.stop_pathfinding(),
+                Danger::LowHealth => {
+                    let settings = ctx.bot.get_component::<SafetySettings>();
+                    let home = settings.and_then(|settings| waypoints::get(&settings.home));
+                    match (home, nearest_monster(&ctx.bot)) {
+                        (Some(home), _) => {
+                            if !going_home {
+                                ctx.log(format!("retreating home to {} {} {}", home.x, home.y, home.z));
+                                ctx.bot.goto(BlockPosGoal(home));
+                                going_home = true;
+                            }
+                        }
+                        (None, Some(monster)) => back_off(&ctx.bot, monster),
+                        // auto-eat takes care of healing
+                        (None, None) => ctx.bot.stop_pathfinding(),
+                    }
+                }
+            }
+        }
+        ticks += 1;
+        ctx.tick().await?;
+    }
+}
+
+/// Walks straight away from `from`
+fn back_off(bot: &Client, from: Vec3) {
+    let position = bot.position();
+    let away = Vec3::new(position.x - from.x, 0.0, position.z - from.z);
+    let length = (away.x * away.x + away.z * away.z).sqrt().max(0.1);
+    let goal = position + Vec3::new(away.x / length, 0.0, away.z / length) * BACK_OFF_DISTANCE;
+    bot.goto_without_mining(RadiusGoal { pos: goal, radius: 2.0 });
+}
+
+/// Closest monster around, whatever killaura is set to fight. Only the ECS is
+/// locked, the schedule holds it while writing the world
+fn nearest_monster(bot: &Client) -> Option<Vec3> {
+    let position = bot.position();
+    let instance_name = bot.get_component::<InstanceName>()?;
+    let mut ecs = bot.ecs.lock();
+    let mut query = ecs.query_filtered::<(&Position, &InstanceName), (
+        With<AbstractMonster>,
+        Without<LocalEntity>,
+        Without<Dead>,
+    )>();
+    query
+        .iter(&ecs)
+        .filter(|(_, instance)| **instance == instance_name)
+        .map(|(monster, _)| **monster)
+        .filter(|monster| monster.distance_to(&position) < MONSTER_RANGE)
+        .min_by(|a, b| a.distance_to(&position).total_cmp(&b.distance_to(&position)))
+}
+
+/// Closest block to stand on with air for the feet and head and nothing
+/// burning next to it
+fn safe_spot(bot: &Client) -> Option<BlockPos> {
+    let position = bot.position();
+    let feet = BlockPos::from(position);
+    let world = bot.world();
+    let world = world.read();
+    let r = SAFE_SEARCH_RADIUS;
+    (-r..=r)
+        .flat_map(|x| (-r..=r).flat_map(move |y| (-r..=r).map(move |z| feet + BlockPos::new(x, y, z))))
+        .filter(|pos| is_safe(&world, *pos))
+        .min_by(|a, b| a.center().distance_squared_to(&position).total_cmp(&b.center().distance_squared_to(&position)))
+}
+
+fn is_safe(world: &Instance, feet: BlockPos) -> bool {
+    let state = |pos: BlockPos| world.get_block_state(&pos);
+    let air = |pos: BlockPos| state(pos).is_some_and(|state| state.is_air());
+    let burning = |state: BlockState| {
+        matches!(Block::from(state), Block::Lava | Block::Fire | Block::SoulFire | Block::MagmaBlock)
+    };
+    let solid = |pos: BlockPos| state(pos).is_some_and(|below| below.is_collision_shape_full() && !burning(below));
+    if !air(feet) || !air(feet.up(1)) || !solid(feet.down(1)) {
+        return false;
+    }
+    let around = [BlockPos::new(1, 0, 0), BlockPos::new(-1, 0, 0), BlockPos::new(0, 0, 1), BlockPos::new(0, 0, -1)];
+    !around.into_iter().any(|offset| state(feet + offset).is_some_and(burning))
+}
@@ -0,0 +1,38 @@
+use azalea::{
+    app::{App, Plugin, Update},
+    ecs::prelude::*,
+    packet::game::ReceivePacketEvent,
+    prelude::*,
+    protocol::packets::game::ClientboundGamePacket,
+};
+
+/// Keeps the XP bar of every bot, azalea reads the packet but drops it
+pub struct ExperiencePlugin;
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, Self::handle_experience);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Experience {
+    pub level: u32,
+    /// Progress towards the next level, 0 to 1
+    pub progress: f32,
+    pub total: u32,
+}
+
+impl ExperiencePlugin {
+    fn handle_experience(mut events: EventReader<ReceivePacketEvent>, mut commands: Commands) {
+        for event in events.read() {
+            if let ClientboundGamePacket::SetExperience(packet) = &*event.packet {
+                commands.entity(event.entity).insert(Experience {
+                    level: packet.experience_level,
+                    progress: packet.experience_progress,
+                    total: packet.total_experience,
+                });
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use azalea::app::{PluginGroup, PluginGroupBuilder};
 
+pub mod experience;
 pub mod game_tick;
 
 pub struct TrackersGroup;
@@ -8,5 +9,6 @@ impl PluginGroup for TrackersGroup {
     fn build(self) -> PluginGroupBuilder {
         PluginGroupBuilder::start::<Self>()
             .add(game_tick::GameTickPlugin)
+            .add(experience::ExperiencePlugin)
     }
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
+
+use azalea::BlockPos;
+
+/// Named spots from the `[waypoints]` table of the config
+static WAYPOINTS: OnceLock<BTreeMap<String, BlockPos>> = OnceLock::new();
+
+/// Stores the `[waypoints]` of the config, names are looked up ignoring case
+pub fn load(waypoints: &HashMap<String, [i32; 3]>) -> Result<(), String> {
+    let waypoints = waypoints
+        .iter()
+        .map(|(name, [x, y, z])| (name.to_lowercase(), BlockPos::new(*x, *y, *z)))
+        .collect();
+    WAYPOINTS.set(waypoints).map_err(|_| "waypoints were already loaded".to_string())
+}
+
+pub fn get(name: &str) -> Option<BlockPos> {
+    WAYPOINTS.get()?.get(&name.to_lowercase()).copied()
+}
+
+/// Every waypoint name, sorted
+pub fn names() -> Vec<String> {
+    WAYPOINTS.get().map(|waypoints| waypoints.keys().cloned().collect()).unwrap_or_default()
+}
@@ -0,0 +1,445 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use azalea::{registry::Item, BlockPos};
+use color_eyre::{eyre::{bail, eyre, WrapErr}, Result};
+use serde::Deserialize;
+
+use crate::azal::{
+    block_names::registry_names,
+    chat_control::Permission,
+    modules::{
+        auto_armor::AutoArmorSettings, auto_eat::AutoEatSettings, farming::FarmingSettings, killaura::KillauraSettings,
+        safety::SafetySettings,
+    },
+    settings::ModuleSettings,
+    storage::ITEM_GROUPS,
+    waypoints,
+};
+
+/// Config file read when `UNYX_CONFIG` is not set
+pub const DEFAULT_CONFIG_PATH: &str = "unyx.toml";
+/// Overrides every other source of the auth-plugin password
+pub const PASSWORD_ENV: &str = "UNYX_AUTH_PASSWORD";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub server: String,
+    pub accounts: Vec<AccountConfig>,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub modules: ModulesConfig,
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
+    #[serde(default)]
+    pub tui: TuiConfig,
+    #[serde(default)]
+    pub chat_control: ChatControlConfig,
+    #[serde(default)]
+    pub scripts: ScriptsConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub crafting: CraftingConfig,
+    /// Extra `#name`s for `mine`, each a list of block names and `#tags`
+    #[serde(default)]
+    pub block_groups: HashMap<String, Vec<String>>,
+    /// Named spots for `goto <name>`, as `[x, y, z]`
+    #[serde(default)]
+    pub waypoints: HashMap<String, [i32; 3]>,
+}
+
+/// A bot of the swarm and the groups it can be addressed by
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountConfig {
+    pub name: String,
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// Password for servers running a `/login` plugin such as AuthMe
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    pub password: Option<String>,
+    /// File holding only the password, must not be readable by other users
+    pub password_file: Option<PathBuf>,
+}
+
+/// Module settings every bot starts with, changed at runtime with `set`
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModulesConfig {
+    pub killaura: KillauraSettings,
+    pub auto_eat: AutoEatSettings,
+    pub auto_armor: AutoArmorSettings,
+    pub safety: SafetySettings,
+    pub farming: FarmingSettings,
+}
+
+/// How long a dropped bot waits before each attempt to join again
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReconnectConfig {
+    pub enabled: bool,
+    /// Wait before the first attempt, doubled after every failed one
+    pub initial_delay_secs: f64,
+    pub max_delay_secs: f64,
+    /// Random spread of every wait, 0.2 makes it up to 20% shorter or longer
+    pub jitter: f64,
+    /// Failed attempts in a row before giving up on a bot, 0 never gives up
+    pub max_attempts: u32,
+    /// A bot that stayed online this long starts over at the initial delay
+    pub stable_after_secs: f64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            initial_delay_secs: 5.0,
+            max_delay_secs: 300.0,
+            jitter: 0.2,
+            max_attempts: 0,
+            stable_after_secs: 60.0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TuiConfig {
+    /// Lines each log pane keeps before dropping the oldest
+    pub scrollback: usize,
+    /// Where typed commands are kept between sessions, none to not keep them
+    pub history_file: Option<PathBuf>,
+    pub history_size: usize,
+    /// Cells from the bot to the edge of the map, before zooming out
+    pub minimap_radius: u32,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            scrollback: 5000,
+            history_file: Some(PathBuf::from("unyx.history")),
+            history_size: 1000,
+            minimap_radius: 24,
+        }
+    }
+}
+
+/// Commands whitelisted players whisper or say in game, off unless enabled
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChatControlConfig {
+    pub enabled: bool,
+    /// Marks a command in public chat, whispers work without it
+    pub prefix: String,
+    /// Server command replies are whispered with, `msg`, `tell` or `w`
+    pub whisper_command: String,
+    /// Player names and what they may do, `view`, `control` or `admin`
+    pub players: HashMap<String, Permission>,
+}
+
+impl Default for ChatControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefix: "!".into(),
+            whisper_command: "msg".into(),
+            players: HashMap::new(),
+        }
+    }
+}
+
+impl ChatControlConfig {
+    /// Level of `player`, `None` when they are not whitelisted
+    pub fn permission(&self, player: &str) -> Option<Permission> {
+        self.players.iter().find(|(name, _)| name.eq_ignore_ascii_case(player)).map(|(_, level)| *level)
+    }
+}
+
+/// Where `run <script>` finds `<script>.rhai`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptsConfig {
+    /// Script logs go in a `logs` directory inside it
+    pub dir: PathBuf,
+}
+
+impl Default for ScriptsConfig {
+    fn default() -> Self {
+        Self { dir: PathBuf::from("scripts") }
+    }
+}
+
+/// Chests `deposit` fills and what goes into them
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// Tried in order, none to use the nearest chest or barrel
+    pub chests: Vec<SpotConfig>,
+    /// Item names, `#gear` or `#food` that are never deposited
+    pub keep: Vec<String>,
+    /// Only these are deposited when set, anything not kept otherwise
+    pub deposit: Vec<String>,
+    /// Bots with fewer free inventory slots deposit on their own, 0 never does
+    pub min_free_slots: usize,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            chests: Vec::new(),
+            keep: vec!["#gear".into(), "#food".into()],
+            deposit: Vec::new(),
+            min_free_slots: 3,
+        }
+    }
+}
+
+/// Where `craft` gets recipes from and which crafting tables it may use
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CraftingConfig {
+    /// A vanilla recipe export, every `.json` below it is read
+    pub recipes: PathBuf,
+    /// The first one is used for 3x3 recipes, without any the nearest table
+    pub tables: Vec<SpotConfig>,
+}
+
+impl Default for CraftingConfig {
+    fn default() -> Self {
+        Self { recipes: PathBuf::from("recipes"), tables: Vec::new() }
+    }
+}
+
+/// A block in the world, as `[x, y, z]` or a waypoint name
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SpotConfig {
+    Pos([i32; 3]),
+    Waypoint(String),
+}
+
+impl SpotConfig {
+    /// The block it names, waypoints have to be loaded already
+    pub fn resolve(&self) -> Option<BlockPos> {
+        match self {
+            SpotConfig::Pos([x, y, z]) => Some(BlockPos::new(*x, *y, *z)),
+            SpotConfig::Waypoint(name) => waypoints::get(name),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the file named by `UNYX_CONFIG`, or `unyx.toml`
+    pub fn load() -> Result<Self> {
+        let path = std::env::var_os("UNYX_CONFIG")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+        Self::from_file(&path)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).wrap_err_with(|| {
+            format!("could not read config {}, copy unyx.example.toml to get started", path.display())
+        })?;
+        let config: Config = toml::from_str(&text)
+            .wrap_err_with(|| format!("invalid config {}", path.display()))?;
+        config.validate().wrap_err_with(|| format!("invalid config {}", path.display()))?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let (host, port) = self.server.rsplit_once(':').unwrap_or((&self.server, "25565"));
+        if host.trim().is_empty() {
+            bail!("`server` must be a host name, optionally followed by `:port`");
+        }
+        if port.parse::<u16>().is_err() {
+            bail!("`server` has an invalid port `{port}`");
+        }
+
+        if self.accounts.is_empty() {
+            bail!("at least one [[accounts]] entry is required");
+        }
+        let mut names = HashSet::new();
+        for account in &self.accounts {
+            let name = &account.name;
+            if !(3..=16).contains(&name.len())
+                || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                bail!("account name `{name}` must be 3-16 letters, digits or underscores");
+            }
+            if !names.insert(name.to_lowercase()) {
+                bail!("account `{name}` is listed twice");
+            }
+        }
+        for account in &self.accounts {
+            for group in &account.groups {
+                if group.eq_ignore_ascii_case("all") {
+                    bail!("group name `all` on account `{}` is reserved for @all", account.name);
+                }
+                if names.contains(&group.to_lowercase()) {
+                    bail!("group `{group}` on account `{}` has the same name as a bot", account.name);
+                }
+            }
+        }
+
+        for (group, blocks) in &self.block_groups {
+            if group.is_empty() || !group.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("block group `{group}` must only use letters, digits or underscores");
+            }
+            if blocks.is_empty() {
+                bail!("block group `{group}` is empty");
+            }
+        }
+
+        let reconnect = &self.reconnect;
+        let floats = [
+            ("initial_delay_secs", reconnect.initial_delay_secs),
+            ("max_delay_secs", reconnect.max_delay_secs),
+            ("jitter", reconnect.jitter),
+            ("stable_after_secs", reconnect.stable_after_secs),
+        ];
+        for (key, value) in floats {
+            if !value.is_finite() {
+                bail!("`reconnect.{key}` must be a finite number");
+            }
+        }
+        if reconnect.initial_delay_secs <= 0.0 {
+            bail!("`reconnect.initial_delay_secs` must be above 0");
+        }
+        if reconnect.max_delay_secs < reconnect.initial_delay_secs {
+            bail!("`reconnect.max_delay_secs` must not be below `reconnect.initial_delay_secs`");
+        }
+        if !(0.0..=1.0).contains(&reconnect.jitter) {
+            bail!("`reconnect.jitter` must be between 0 and 1");
+        }
+        if reconnect.stable_after_secs < 0.0 {
+            bail!("`reconnect.stable_after_secs` must not be negative");
+        }
+
+        let modules = &self.modules;
+        let settings: [(&str, &dyn ModuleSettings); 5] = [
+            ("killaura", &modules.killaura),
+            ("auto_eat", &modules.auto_eat),
+            ("auto_armor", &modules.auto_armor),
+            ("safety", &modules.safety),
+            ("farming", &modules.farming),
+        ];
+        for (module, settings) in settings {
+            settings.validate().map_err(|e| eyre!("[modules.{module}] {e}"))?;
+        }
+
+        for waypoint in self.waypoints.keys() {
+            if waypoint.is_empty() || !waypoint.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("waypoint `{waypoint}` must only use letters, digits or underscores");
+            }
+            if waypoint.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+                bail!("waypoint `{waypoint}` must not start with a digit, it would read as a coordinate");
+            }
+        }
+
+        let storage = &self.storage;
+        self.check_spots("storage chest", &storage.chests)?;
+        let items = registry_names::<Item>();
+        for rule in storage.keep.iter().chain(&storage.deposit) {
+            let name = rule.trim_start_matches("minecraft:");
+            if !ITEM_GROUPS.contains(&name) && !items.iter().any(|item| item.eq_ignore_ascii_case(name)) {
+                bail!("storage rule `{rule}` must be an item name or one of {}", ITEM_GROUPS.join(", "));
+            }
+        }
+
+        self.check_spots("crafting table", &self.crafting.tables)?;
+
+        if self.tui.scrollback == 0 {
+            bail!("`tui.scrollback` must be at least 1");
+        }
+        if !(1..=64).contains(&self.tui.minimap_radius) {
+            bail!("`tui.minimap_radius` must be between 1 and 64");
+        }
+
+        let chat_control = &self.chat_control;
+        if chat_control.prefix.is_empty()
+            || chat_control.prefix.starts_with('/')
+            || chat_control.prefix.contains(char::is_whitespace)
+        {
+            bail!("`chat_control.prefix` must not be empty, start with `/` or contain spaces");
+        }
+        if chat_control.whisper_command.is_empty() || chat_control.whisper_command.contains(char::is_whitespace) {
+            bail!("`chat_control.whisper_command` must be a single command name like `msg`");
+        }
+        for player in chat_control.players.keys() {
+            if !(3..=16).contains(&player.len()) || !player.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                bail!("chat_control player `{player}` must be 3-16 letters, digits or underscores");
+            }
+        }
+
+        if let Some(path) = &self.auth.password_file {
+            check_secret_permissions(path)?;
+        }
+        Ok(())
+    }
+
+    /// Fails on a spot naming a waypoint that doesn't exist
+    fn check_spots(&self, what: &str, spots: &[SpotConfig]) -> Result<()> {
+        for spot in spots {
+            if let SpotConfig::Waypoint(name) = spot
+                && !self.waypoints.keys().any(|waypoint| waypoint.eq_ignore_ascii_case(name))
+            {
+                bail!("{what} `{name}` is not a waypoint");
+            }
+        }
+        Ok(())
+    }
+
+    /// Password for the server's login plugin, taken from `UNYX_AUTH_PASSWORD`,
+    /// then `auth.password_file`, then `auth.password`
+    pub fn auth_password(&self) -> Result<Option<String>> {
+        if let Ok(password) = std::env::var(PASSWORD_ENV) {
+            return Ok(Some(password));
+        }
+        if let Some(path) = &self.auth.password_file {
+            let password = std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("could not read password file {}", path.display()))?;
+            let password = password.trim();
+            if password.is_empty() {
+                bail!("password file {} is empty", path.display());
+            }
+            return Ok(Some(password.to_string()));
+        }
+        Ok(self.auth.password.clone())
+    }
+}
+
+#[cfg(unix)]
+fn check_secret_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = std::fs::metadata(path)
+        .wrap_err_with(|| format!("could not read password file {}", path.display()))?
+        .permissions()
+        .mode();
+    if mode & 0o077 != 0 {
+        bail!(
+            "password file {} is accessible by other users (mode {:o}), run `chmod 600` on it",
+            path.display(),
+            mode & 0o777
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_secret_permissions(path: &Path) -> Result<()> {
+    std::fs::metadata(path)
+        .wrap_err_with(|| format!("could not read password file {}", path.display()))?;
+    Ok(())
+}
@@ -1,14 +1,16 @@
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
+use ratatui::crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+};
 
 
 mod rats;
 mod azal;
+mod config;
 
-use azal::ConsoleType;
-use azal::CommandType;
-
-// const SERVER_ADDRESS: &'static str = "RifaiNq.aternos.me:26222";
-const SERVER_ADDRESS: &'static str = "emerald.magmanode.com:29769";
+use azal::{status::BotStatus, ConsoleType};
+use azal::TargetedCommand;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -16,11 +18,22 @@ async fn main() -> Result<()> {
         std::env::set_var("RUST_LOG", "warn,azalea_client::plugins::packet::game::events=off");
     }
     color_eyre::install()?;
+    // Fail before the terminal is taken over so config errors stay readable
+    let config = config::Config::load()?;
+    let login_password = config.auth_password()?;
+    azal::block_names::load_groups(&config.block_groups).map_err(|e| eyre!("invalid config: {e}"))?;
+    azal::waypoints::load(&config.waypoints).map_err(|e| eyre!("invalid config: {e}"))?;
+    azal::recipes::load(&config.crafting).map_err(|e| eyre!("invalid config: {e}"))?;
+    azal::scripts::load(&config.scripts).map_err(|e| eyre!("invalid config: {e}"))?;
+    let bot_names: Vec<String> = config.accounts.iter().map(|account| account.name.clone()).collect();
+    let tui = config.tui.clone();
+
     let (tx_log, rx_log) = std::sync::mpsc::channel::<ConsoleType>();
-    let (tx_input, rx_input) = std::sync::mpsc::channel::<CommandType>();
-    std::thread::spawn(move || ratatui_term(rx_log, tx_input));
+    let (tx_input, rx_input) = std::sync::mpsc::channel::<TargetedCommand>();
+    let (tx_status, rx_status) = std::sync::mpsc::channel::<BotStatus>();
+    std::thread::spawn(move || ratatui_term(rx_log, rx_status, tx_input, bot_names, tui));
     std::thread::spawn(|| deadlock_detector());
-    azal::start_azalea(SERVER_ADDRESS, tx_log, rx_input).await?;
+    azal::start_azalea(config, login_password, tx_log, tx_status, rx_input).await?;
 
     Ok(())
 }
@@ -46,33 +59,34 @@ fn deadlock_detector() {
 
 fn ratatui_term(
     rx_log: std::sync::mpsc::Receiver<ConsoleType>, 
-    tx_input: std::sync::mpsc::Sender<CommandType>,
+    rx_status: std::sync::mpsc::Receiver<BotStatus>,
+    tx_input: std::sync::mpsc::Sender<TargetedCommand>,
+    bot_names: Vec<String>,
+    tui: config::TuiConfig,
 ) -> Result<()> {
     let terminal = ratatui::init();
-    let mut rat_app = rats::RatApp::new();
+    // for scrolling the log panes with the mouse wheel
+    execute!(std::io::stdout(), EnableMouseCapture)?;
+    let mut rat_app = rats::RatApp::new(&bot_names, &tui);
     
     // Clone the Arc fields before moving them
-    let bot_log_clone = rat_app.bot_log.clone();
-    let server_msgs_clone = rat_app.server_msgs.clone();
+    let tabs_clone = rat_app.tabs.clone();
 
     std::thread::spawn(move || {
-        loop {
-            match rx_log.recv() {
-                Ok(ConsoleType::Botlog(msg)) => {
-                    let mut bot_log = bot_log_clone.lock().unwrap();
-                    bot_log.push(msg);
-                }
-                Ok(ConsoleType::ServerMsg(msg)) => {
-                    let mut server_msgs = server_msgs_clone.lock().unwrap();
-                    server_msgs.push(msg);
-                }
-                Err(_) => break,
-            }
+        while let Ok(entry) = rx_log.recv() {
+            tabs_clone.lock().unwrap().push(entry);
+        }
+    });
+    let statuses_clone = rat_app.statuses.clone();
+    std::thread::spawn(move || {
+        while let Ok(status) = rx_status.recv() {
+            statuses_clone.lock().unwrap().insert(status.name.clone(), status);
         }
     });
     
     
     let app_result = rat_app.run(terminal, tx_input);
+    let _ = execute!(std::io::stdout(), DisableMouseCapture);
     ratatui::restore();
     app_result
 }
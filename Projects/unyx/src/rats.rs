@@ -1,96 +1,333 @@
+mod chat;
+mod input;
+mod inventory;
+mod minimap;
+mod scrollback;
+mod status;
+
 use color_eyre::Result;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
-    layout::{Constraint, Layout, Position},
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind},
+    layout::{Constraint, Layout, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
-    text::{Line, Span, Text},
-    widgets::{Block, List, ListItem, Paragraph},
+    text::{Line, Text},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     DefaultTerminal, Frame,
 };
+use input::Input;
+use inventory::InventoryCursor;
+use scrollback::{highlight, Scrollback};
+
+use crate::azal::{
+    command,
+    minimap::{Minimap, MinimapRequest},
+    status::BotStatus,
+    CommandType,
+    ConsoleType,
+    Target,
+    TargetedCommand,
+};
+use crate::config::TuiConfig;
+
+const SWARM_TAB: &str = "swarm";
+/// Lines one mouse wheel step scrolls
+const WHEEL_ROWS: usize = 3;
+/// Width of the status panel next to the log panes
+const STATUS_WIDTH: u16 = 36;
+/// Tab candidates listed when the word can't be completed any further
+const MAX_LISTED_CANDIDATES: usize = 20;
+
+pub struct LogPane {
+    pub bot_log: Scrollback,
+    pub server_msgs: Scrollback,
+}
+
+impl LogPane {
+    fn new(scrollback: usize) -> Self {
+        Self { bot_log: Scrollback::new(scrollback), server_msgs: Scrollback::new(scrollback) }
+    }
+
+    fn log_mut(&mut self, kind: PaneKind) -> &mut Scrollback {
+        match kind {
+            PaneKind::BotLog => &mut self.bot_log,
+            PaneKind::ServerMsgs => &mut self.server_msgs,
+        }
+    }
+}
+
+/// The swarm tab followed by one tab per bot
+pub struct LogTabs {
+    names: Vec<String>,
+    panes: Vec<LogPane>,
+    /// Lines each pane keeps
+    scrollback: usize,
+    /// Tab list of every bot, for completing player names
+    players: HashMap<String, Vec<String>>,
+    /// Latest map of every bot that was sent one
+    minimaps: HashMap<String, Minimap>,
+}
 
-use crate::azal::CommandType;
+impl LogTabs {
+    pub fn new(bots: &[String], scrollback: usize) -> Self {
+        let names: Vec<String> = std::iter::once(SWARM_TAB.to_string())
+            .chain(bots.iter().cloned())
+            .collect();
+        let panes = names.iter().map(|_| LogPane::new(scrollback)).collect();
+        Self { names, panes, scrollback, players: HashMap::new(), minimaps: HashMap::new() }
+    }
+
+    fn index_of(&mut self, bot: &str) -> usize {
+        if let Some(idx) = self.names.iter().position(|name| name == bot) {
+            return idx;
+        }
+        self.names.push(bot.to_string());
+        self.panes.push(LogPane::new(self.scrollback));
+        self.names.len() - 1
+    }
 
+    pub fn push(&mut self, entry: ConsoleType) {
+        match entry {
+            ConsoleType::Console(msg) => self.panes[0].bot_log.push(msg),
+            ConsoleType::Botlog { bot, msg } => {
+                let idx = self.index_of(&bot);
+                self.panes[0].bot_log.push(format!("[{bot}] {msg}"));
+                self.panes[idx].bot_log.push(msg);
+            }
+            ConsoleType::ServerMsg { bot, msg } => {
+                let idx = self.index_of(&bot);
+                let msg = chat::to_log_line(&msg);
+                // every bot hears the same chat, so the swarm tab only
+                // mirrors the first one
+                if idx == 1 {
+                    self.panes[0].server_msgs.push(msg.clone());
+                }
+                self.panes[idx].server_msgs.push(msg);
+            }
+            ConsoleType::Players { bot, names } => {
+                self.players.insert(bot, names);
+            }
+            ConsoleType::Minimap { bot, map } => {
+                self.minimaps.insert(bot, map);
+            }
+        }
+    }
+}
 
 pub struct RatApp {
-    input: String,
-    char_idx: usize,
+    input: Input,
     input_mode: InputMode,
-    pub bot_log: Arc<Mutex<Vec<String>>>,
-    pub server_msgs: Arc<Mutex<Vec<String>>>,
+    selected_tab: usize,
+    /// Pane that scrolling keys and `n`/`N` act on
+    focus: PaneKind,
+    /// Highlighted in both panes, empty when not searching
+    search: String,
+    inventory: InventoryCursor,
+    /// Shown instead of the server messages while open
+    minimap: Option<MinimapRequest>,
+    minimap_radius: u32,
+    /// Bot the open map was last asked of, and how
+    minimap_sent: Option<(String, MinimapRequest)>,
+    /// Where the panes were drawn last, to tell which one the mouse is over
+    pane_areas: [(PaneKind, Rect); 2],
+    pub tabs: Arc<Mutex<LogTabs>>,
+    /// Latest snapshot of every bot, filled from the status channel
+    pub statuses: Arc<Mutex<BTreeMap<String, BotStatus>>>,
 }
 
 enum InputMode {
     Normal,
     Insert,
+    Search,
+    /// The inventory grid of the bot tab replaces the log panes
+    Inventory,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PaneKind {
+    BotLog,
+    ServerMsgs,
 }
 
 impl RatApp {
-    pub fn new() -> Self {
+    pub fn new(bots: &[String], config: &TuiConfig) -> Self {
         Self {
-            input: String::new(),
-            char_idx: 0,
+            input: Input::new(config.history_file.clone(), config.history_size),
             input_mode: InputMode::Normal,
-            bot_log: Arc::new(Mutex::new(Vec::new())),
-            server_msgs: Arc::new(Mutex::new(Vec::new())),
+            selected_tab: 0,
+            focus: PaneKind::BotLog,
+            search: String::new(),
+            inventory: InventoryCursor::default(),
+            minimap: None,
+            minimap_radius: config.minimap_radius,
+            minimap_sent: None,
+            pane_areas: [(PaneKind::BotLog, Rect::default()), (PaneKind::ServerMsgs, Rect::default())],
+            tabs: Arc::new(Mutex::new(LogTabs::new(bots, config.scrollback))),
+            statuses: Arc::new(Mutex::new(BTreeMap::new())),
         }
     }
 
-    fn move_cursor_left(&mut self) {
-        let new_idx = self.char_idx.saturating_sub(1);
-        self.char_idx = self.clamp_cursor(new_idx);
+    fn with_log<R>(&self, kind: PaneKind, f: impl FnOnce(&mut Scrollback) -> R) -> Option<R> {
+        let mut tabs = self.tabs.lock().ok()?;
+        Some(f(tabs.panes[self.selected_tab].log_mut(kind)))
     }
 
-    fn move_cursor_right(&mut self) {
-        let new_idx = self.char_idx.saturating_add(1);
-        self.char_idx = self.clamp_cursor(new_idx);
+    /// Rows of the focused pane, one page of scrolling
+    fn page_rows(&self) -> usize {
+        let height = self.pane_areas.iter().find(|(kind, _)| *kind == self.focus).map_or(0, |(_, area)| area.height);
+        usize::from(height.saturating_sub(3)).max(1)
     }
 
-    fn enter_char(&mut self, c: char) {
-        let idx = self.byte_index();
-        self.input.insert(idx, c);
-        self.move_cursor_right();
+    fn scroll_at(&mut self, column: u16, row: u16, up: bool) {
+        let position = Position::new(column, row);
+        let Some((kind, _)) = self.pane_areas.iter().find(|(_, area)| area.contains(position)).copied() else {
+            return;
+        };
+        self.with_log(kind, |log| if up { log.scroll_up(WHEEL_ROWS) } else { log.scroll_down(WHEEL_ROWS) });
+    }
+
+    /// Jumps both panes to their newest line matching the search so far
+    fn update_search(&self) {
+        for kind in [PaneKind::BotLog, PaneKind::ServerMsgs] {
+            self.with_log(kind, |log| log.find(&self.search, true, false));
+        }
+    }
+
+    fn next_match(&self, older: bool) {
+        self.with_log(self.focus, |log| log.find(&self.search, older, true));
+    }
+
+    fn next_tab(&mut self) {
+        let len = self.tabs.lock().map(|tabs| tabs.names.len()).unwrap_or(1);
+        self.selected_tab = (self.selected_tab + 1) % len;
+    }
+
+    fn previous_tab(&mut self) {
+        let len = self.tabs.lock().map(|tabs| tabs.names.len()).unwrap_or(1);
+        self.selected_tab = (self.selected_tab + len - 1) % len;
+    }
+
+    /// Bots addressed by a command without an `@target` prefix
+    fn default_target(&self) -> Target {
+        match self.tabs.lock() {
+            Ok(tabs) if self.selected_tab > 0 => Target::Named(tabs.names[self.selected_tab].clone()),
+            _ => Target::All,
+        }
+    }
+
+    /// Hands a key to the inventory grid, sending whatever it asks the bot
+    /// of the tab to do
+    fn inventory_key(&mut self, code: KeyCode, tx_input: &std::sync::mpsc::Sender<TargetedCommand>) {
+        let Target::Named(bot) = self.default_target() else {
+            return;
+        };
+        let Some(view) = self.statuses.lock().ok().and_then(|statuses| Some(statuses.get(&bot)?.inventory.clone())) else {
+            return;
+        };
+        if let Some(action) = self.inventory.key(code, &view) {
+            let _ = tx_input.send(TargetedCommand { target: Target::Named(bot), command: CommandType::Inventory(action) });
+        }
+    }
+
+    fn toggle_minimap(&mut self) {
+        self.minimap = match self.minimap {
+            Some(_) => None,
+            None => Some(MinimapRequest { radius: self.minimap_radius, scale: minimap::MIN_SCALE, slice: None }),
+        };
     }
 
-    fn byte_index(&self) -> usize {
-        self.input
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.char_idx)
-            .unwrap_or(self.input.len())
+    fn change_minimap(&mut self, f: impl FnOnce(&mut MinimapRequest)) {
+        if let Some(request) = &mut self.minimap {
+            f(request);
+        }
     }
 
-    fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.char_idx != 0;
-        if is_not_cursor_leftmost {
-            let cur_idx = self.char_idx;
-            let from_left_to_cur_idx = cur_idx - 1;
-            let before_char_to_delete = self.input.chars().take(from_left_to_cur_idx);
-            let after_char_to_delete = self.input.chars().skip(cur_idx);
-            self.input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+    /// Asks the bot of the tab for maps while the map is open, and tells a
+    /// bot to stop once it is closed or another tab is picked
+    fn sync_minimap(&mut self, tx_input: &std::sync::mpsc::Sender<TargetedCommand>) {
+        let wanted = match (self.minimap, self.default_target()) {
+            (Some(request), Target::Named(bot)) => Some((bot, request)),
+            _ => None,
+        };
+        if wanted == self.minimap_sent {
+            return;
+        }
+        let send = |bot: &str, request| {
+            let command = CommandType::Minimap(request);
+            let _ = tx_input.send(TargetedCommand { target: Target::Named(bot.to_string()), command });
+        };
+        if let Some((bot, _)) = &self.minimap_sent
+            && wanted.as_ref().is_none_or(|(wanted, _)| wanted != bot)
+        {
+            send(bot, None);
         }
+        if let Some((bot, request)) = &wanted {
+            send(bot, Some(*request));
+        }
+        self.minimap_sent = wanted;
     }
 
-    fn reset_cursor(&mut self) {
-        self.char_idx = 0;
+    /// Completes the word before the cursor, listing the candidates when
+    /// more than one fits
+    fn complete(&mut self) {
+        let (players, bots) = match self.tabs.lock() {
+            Ok(tabs) => {
+                let mut players = tabs.players.values().flatten().cloned().collect::<Vec<_>>();
+                players.sort();
+                players.dedup();
+                (players, tabs.names[1..].to_vec())
+            }
+            Err(_) => return,
+        };
+        let completion = command::complete(self.input.before_cursor(), &players, &bots);
+        let word = &self.input.before_cursor()[completion.start..];
+        match completion.candidates.as_slice() {
+            [] => {}
+            [only] => {
+                let only = format!("{only} ");
+                self.input.replace_before_cursor(completion.start, &only);
+            }
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.as_str(), |common, candidate| {
+                    let len = common
+                        .char_indices()
+                        .zip(candidate.chars())
+                        .take_while(|((_, a), b)| a.eq_ignore_ascii_case(b))
+                        .last()
+                        .map_or(0, |((idx, a), _)| idx + a.len_utf8());
+                    &common[..len]
+                });
+                if common.len() > word.len() {
+                    let common = common.to_string();
+                    self.input.replace_before_cursor(completion.start, &common);
+                    return;
+                }
+                let mut listed = completion.candidates.iter().take(MAX_LISTED_CANDIDATES).cloned().collect::<Vec<_>>();
+                if completion.candidates.len() > MAX_LISTED_CANDIDATES {
+                    listed.push(format!("and {} more", completion.candidates.len() - MAX_LISTED_CANDIDATES));
+                }
+                self.log(listed.join("  "));
+            }
+        }
     }
 
-    fn submit_msg(&mut self, tx_input: &std::sync::mpsc::Sender<CommandType>) {
-        self.process_command(tx_input);
-        if let Ok(mut bot_log) = self.bot_log.lock() {
-            bot_log.push(self.input.clone());
+    fn log(&self, msg: String) {
+        if let Ok(mut tabs) = self.tabs.lock() {
+            tabs.panes[self.selected_tab].bot_log.push(msg);
         }
-        self.input.clear();
-        self.reset_cursor();
     }
 
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.input.chars().count())
+    fn submit_msg(&mut self, tx_input: &std::sync::mpsc::Sender<TargetedCommand>) {
+        let line = self.input.submit();
+        self.log(line.clone());
+        if let Err(e) = self.process_command(&line, tx_input) {
+            self.log(format!("error: {e}"));
+        }
     }
 
-    pub fn run(&mut self, mut terminal: DefaultTerminal, tx_input: std::sync::mpsc::Sender<CommandType>) -> Result<()> {
+    pub fn run(&mut self, mut terminal: DefaultTerminal, tx_input: std::sync::mpsc::Sender<TargetedCommand>) -> Result<()> {
         
         let mut last_draw = Instant::now();
         let draw_interval = Duration::from_millis(200);
@@ -105,47 +342,162 @@ impl RatApp {
             }
             
             if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    match self.input_mode {
-                        InputMode::Normal => match key.code {
-                            KeyCode::Char('i') => {
-                                self.input_mode = InputMode::Insert;
-                            }
-                            KeyCode::Char('q') => {
-                                return Ok(());
-                            }
+                let key = match event::read()? {
+                    Event::Key(key) => key,
+                    Event::Mouse(mouse) => {
+                        match mouse.kind {
+                            MouseEventKind::ScrollUp => self.scroll_at(mouse.column, mouse.row, true),
+                            MouseEventKind::ScrollDown => self.scroll_at(mouse.column, mouse.row, false),
                             _ => {}
-                        },
-                        InputMode::Insert if key.kind == KeyEventKind::Press => match key.code {
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+                match self.input_mode {
+                    InputMode::Normal => match key.code {
+                        KeyCode::Char('i') => {
+                            self.input_mode = InputMode::Insert;
+                        }
+                        KeyCode::Char('q') => {
+                            return Ok(());
+                        }
+                        KeyCode::Tab => self.next_tab(),
+                        KeyCode::BackTab => self.previous_tab(),
+                        KeyCode::Left => self.focus = PaneKind::BotLog,
+                        KeyCode::Right => self.focus = PaneKind::ServerMsgs,
+                        KeyCode::PageUp => {
+                            let rows = self.page_rows();
+                            self.with_log(self.focus, |log| log.scroll_up(rows));
+                        }
+                        KeyCode::PageDown => {
+                            let rows = self.page_rows();
+                            self.with_log(self.focus, |log| log.scroll_down(rows));
+                        }
+                        // move the selection when there is one, scroll otherwise
+                        KeyCode::Up => {
+                            self.with_log(self.focus, |log| {
+                                if log.has_selection() { log.move_selection(true) } else { log.scroll_up(1) }
+                            });
+                        }
+                        KeyCode::Down => {
+                            self.with_log(self.focus, |log| {
+                                if log.has_selection() { log.move_selection(false) } else { log.scroll_down(1) }
+                            });
+                        }
+                        KeyCode::Enter => {
+                            self.with_log(self.focus, Scrollback::toggle_selection);
+                        }
+                        KeyCode::Char('f') => {
+                            self.with_log(self.focus, Scrollback::toggle_follow);
+                        }
+                        KeyCode::Char('/') => {
+                            self.search.clear();
+                            self.input_mode = InputMode::Search;
+                        }
+                        KeyCode::Char('e') if self.selected_tab > 0 => self.input_mode = InputMode::Inventory,
+                        KeyCode::Char('e') => self.log("pick a bot tab to see its inventory".to_string()),
+                        KeyCode::Char('m') => {
+                            self.toggle_minimap();
+                            self.focus = PaneKind::BotLog;
+                        }
+                        KeyCode::Char('+' | '=') => {
+                            self.change_minimap(|map| map.scale = (map.scale / 2).max(minimap::MIN_SCALE));
+                        }
+                        KeyCode::Char('-') => {
+                            self.change_minimap(|map| map.scale = (map.scale * 2).min(minimap::MAX_SCALE));
+                        }
+                        KeyCode::Char('y') => {
+                            self.change_minimap(|map| map.slice = if map.slice.is_some() { None } else { Some(0) });
+                        }
+                        KeyCode::Char('[') => self.change_minimap(|map| map.slice = map.slice.map(|y| y - 1)),
+                        KeyCode::Char(']') => self.change_minimap(|map| map.slice = map.slice.map(|y| y + 1)),
+                        KeyCode::Char('n') => self.next_match(true),
+                        KeyCode::Char('N') => self.next_match(false),
+                        KeyCode::Esc => {
+                            self.search.clear();
+                            self.with_log(self.focus, Scrollback::clear_selection);
+                        }
+                        _ => {}
+                    },
+                    InputMode::Search if key.kind == KeyEventKind::Press => match key.code {
+                        KeyCode::Enter => self.input_mode = InputMode::Normal,
+                        KeyCode::Esc => {
+                            self.search.clear();
+                            self.input_mode = InputMode::Normal;
+                        }
+                        KeyCode::Char(c) => {
+                            self.search.push(c);
+                            self.update_search();
+                        }
+                        KeyCode::Backspace => {
+                            self.search.pop();
+                            self.update_search();
+                        }
+                        _ => {}
+                    },
+                    InputMode::Search => {}
+                    InputMode::Inventory if key.kind == KeyEventKind::Press => match key.code {
+                        KeyCode::Tab => self.next_tab(),
+                        KeyCode::BackTab => self.previous_tab(),
+                        KeyCode::Esc if self.inventory.cancel() => {}
+                        KeyCode::Esc | KeyCode::Char('e') => self.input_mode = InputMode::Normal,
+                        code => self.inventory_key(code, &tx_input),
+                    },
+                    InputMode::Inventory => {}
+                    InputMode::Insert if key.kind == KeyEventKind::Press => {
+                        let word = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                        match key.code {
                             KeyCode::Enter => self.submit_msg(&tx_input),
-                            KeyCode::Char(to_insert) => self.enter_char(to_insert),
-                            KeyCode::Backspace => self.delete_char(),
-                            KeyCode::Left => self.move_cursor_left(),
-                            KeyCode::Right => self.move_cursor_right(),
+                            KeyCode::Tab => self.complete(),
+                            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                                self.input.delete_word_back();
+                            }
+                            KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => self.input.home(),
+                            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => self.input.end(),
+                            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::ALT) => {
+                                self.input.delete_word_forward();
+                            }
+                            KeyCode::Char(to_insert) => self.input.insert(to_insert),
+                            KeyCode::Backspace if word => self.input.delete_word_back(),
+                            KeyCode::Backspace => self.input.backspace(),
+                            KeyCode::Delete if word => self.input.delete_word_forward(),
+                            KeyCode::Delete => self.input.delete(),
+                            KeyCode::Left if word => self.input.word_left(),
+                            KeyCode::Left => self.input.left(),
+                            KeyCode::Right if word => self.input.word_right(),
+                            KeyCode::Right => self.input.right(),
+                            KeyCode::Home => self.input.home(),
+                            KeyCode::End => self.input.end(),
+                            KeyCode::Up => self.input.history_prev(),
+                            KeyCode::Down => self.input.history_next(),
                             KeyCode::Esc => self.input_mode = InputMode::Normal,
                             _ => {}
-                        },
-                        InputMode::Insert => {}
+                        }
                     }
+                    InputMode::Insert => {}
                 }
+                self.sync_minimap(&tx_input);
             }
         }
     }
 
-    fn draw(&self, frame: &mut Frame) {
+    fn draw(&mut self, frame: &mut Frame) {
         let vertical = Layout::vertical([
+            Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(3),
             Constraint::Min(1),
         ]);
-        let [help_area, input_area, logs_area] = vertical.areas(frame.area());
+        let [help_area, tabs_area, input_area, logs_area] = vertical.areas(frame.area());
         
-        // Split the logs area horizontally for bot_log and server_msgs
+        // Split the logs area horizontally for bot_log, server_msgs and the status panel
         let horizontal = Layout::horizontal([
             Constraint::Ratio(1, 2),
             Constraint::Ratio(1, 2),
+            Constraint::Length(STATUS_WIDTH),
         ]);
-        let [bot_log_area, server_msgs_area] = horizontal.areas(logs_area);
+        let [bot_log_area, server_msgs_area, status_area] = horizontal.areas(logs_area);
         
         let (msg, style) = match self.input_mode {
             InputMode::Normal => (
@@ -154,17 +506,72 @@ impl RatApp {
                     "q".bold(),
                     " to exit, ".into(),
                     "i".bold(),
-                    " to start editing.".bold(),
+                    " to start editing, ".into(),
+                    "Tab".bold(),
+                    " to switch bots, ".into(),
+                    "PgUp/PgDn".bold(),
+                    " to scroll, ".into(),
+                    "←/→".bold(),
+                    " to pick a pane, ".into(),
+                    "f".bold(),
+                    " to follow, ".into(),
+                    "/".bold(),
+                    " to search, ".into(),
+                    "Enter".bold(),
+                    " for line details, ".into(),
+                    "e".bold(),
+                    " for the inventory, ".into(),
+                    "m".bold(),
+                    " for the map (".into(),
+                    "+/-".bold(),
+                    " zoom, ".into(),
+                    "y".bold(),
+                    " cave slice, ".into(),
+                    "[/]".bold(),
+                    " slice height).".into(),
                 ],
                 Style::default().add_modifier(Modifier::RAPID_BLINK),
             ),
+            InputMode::Search => (
+                vec![
+                    "Type to search, ".into(),
+                    "Enter".bold(),
+                    " to keep the matches, then ".into(),
+                    "n/N".bold(),
+                    " for older/newer ones, ".into(),
+                    "Esc".bold(),
+                    " to clear.".into(),
+                ],
+                Style::default(),
+            ),
+            InputMode::Inventory => (
+                vec![
+                    "Arrows".bold(),
+                    " to move, ".into(),
+                    "Enter".bold(),
+                    " twice to swap, ".into(),
+                    "d".bold(),
+                    " to drop, ".into(),
+                    "s".bold(),
+                    " to shift-click, ".into(),
+                    "1-9".bold(),
+                    " to hold a hotbar slot, ".into(),
+                    "Esc".bold(),
+                    " to close.".into(),
+                ],
+                Style::default(),
+            ),
             InputMode::Insert => (
                 vec![
                     "Press ".into(),
                     "Esc".bold(),
                     " to exit, ".into(),
                     "Enter".bold(),
-                    " to send.".bold(),
+                    " to send, ".into(),
+                    "Tab".bold(),
+                    " to complete, ".into(),
+                    "↑/↓".bold(),
+                    " for history.".into(),
                 ],
                 Style::default(),
             )
@@ -173,16 +580,50 @@ impl RatApp {
         let help_message = Paragraph::new(text);
         frame.render_widget(help_message, help_area);
 
-        let input = Paragraph::new(self.input.as_str())
-            .style(match self.input_mode {
-                InputMode::Normal => Style::default(),
-                InputMode::Insert => Style::default().fg(Color::Yellow),
-            })
-            .block(Block::bordered().title("Input"));
+        let Ok(mut tabs) = self.tabs.lock() else {
+            return;
+        };
+        // the swarm tab has no inventory, so it keeps showing its logs
+        let show_inventory = matches!(self.input_mode, InputMode::Inventory) && self.selected_tab > 0;
+        if let Ok(statuses) = self.statuses.lock() {
+            let bot = (self.selected_tab > 0).then(|| tabs.names[self.selected_tab].as_str());
+            status::draw_status(frame, status_area, &statuses, bot);
+            if show_inventory {
+                let view = bot.and_then(|bot| statuses.get(bot)).map(|status| &status.inventory);
+                let area = bot_log_area.union(server_msgs_area);
+                inventory::draw_inventory(frame, area, view, &mut self.inventory);
+            }
+        }
+        let tab_titles = tabs.names.clone();
+        frame.render_widget(
+            Tabs::new(tab_titles)
+                .select(self.selected_tab)
+                .highlight_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            tabs_area,
+        );
+        let pane = &mut tabs.panes[self.selected_tab];
+
+        let input = match self.input_mode {
+            InputMode::Search => Paragraph::new(format!("/{}", self.search))
+                .style(Style::default().fg(Color::Cyan))
+                .block(Block::bordered().title("Search")),
+            _ => Paragraph::new(self.input.text())
+                .style(match self.input_mode {
+                    InputMode::Insert => Style::default().fg(Color::Yellow),
+                    _ => Style::default(),
+                })
+                .block(Block::bordered().title("Input")),
+        };
         frame.render_widget(input, input_area);
         match self.input_mode {
             // Hide the cursor. `Frame` does this by default, so we don't need to do anything here
-            InputMode::Normal => {}
+            InputMode::Normal | InputMode::Inventory => {}
+
+            #[allow(clippy::cast_possible_truncation)]
+            InputMode::Search => frame.set_cursor_position(Position::new(
+                input_area.x + self.search.chars().count() as u16 + 2,
+                input_area.y + 1,
+            )),
 
             // Make the cursor visible and ask ratatui to put it at the specified coordinates after
             // rendering
@@ -190,83 +631,94 @@ impl RatApp {
             InputMode::Insert => frame.set_cursor_position(Position::new(
                 // Draw the cursor at the current position in the input field.
                 // This position is can be controlled via the left and right arrow key
-                input_area.x + self.char_idx as u16 + 1,
+                input_area.x + self.input.cursor() as u16 + 1,
                 // Move one line down, from the border to the input line
                 input_area.y + 1,
             )),
         }
 
-        // Bot Log section
-        let bot_messages: Vec<ListItem> = if let Ok(mut bot_log) = self.bot_log.lock() {
-            if bot_log.len() > 10 {
-                bot_log.clear();
-            }
-            bot_log.iter()
-                .map(|m| {
-                    let content = Line::from(Span::raw(format!("{m}")));
-                    ListItem::new(content)
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
-        let bot_messages_list = List::new(bot_messages).block(Block::bordered().title("Bot Log"));
-        frame.render_widget(bot_messages_list, bot_log_area);
-        
-        // Server Messages section
-        let server_messages: Vec<ListItem> = if let Ok(mut server_msgs) = self.server_msgs.lock() {
-            if server_msgs.len() > 10 {
-                server_msgs.clear();
-            }
-            server_msgs.iter()
-                .map(|m| {
-                    let content = Line::from(Span::raw(format!("{m}")));
-                    ListItem::new(content)
-                })
-                .collect()
-        } else {
-            Vec::new()
-        };
-        let server_messages_list = List::new(server_messages).block(Block::bordered().title("Server Messages"));
-        frame.render_widget(server_messages_list, server_msgs_area);
+        if show_inventory {
+            self.pane_areas = [(PaneKind::BotLog, Rect::default()), (PaneKind::ServerMsgs, Rect::default())];
+            return;
+        }
+        draw_log(frame, bot_log_area, &mut pane.bot_log, "Bot Log", self.focus == PaneKind::BotLog, &self.search);
+        if let Some((bot, _)) = &self.minimap_sent {
+            self.pane_areas = [(PaneKind::BotLog, bot_log_area), (PaneKind::ServerMsgs, Rect::default())];
+            minimap::draw_minimap(frame, server_msgs_area, tabs.minimaps.get(bot));
+            return;
+        }
+        self.pane_areas = [(PaneKind::BotLog, bot_log_area), (PaneKind::ServerMsgs, server_msgs_area)];
+        draw_log(
+            frame,
+            server_msgs_area,
+            &mut pane.server_msgs,
+            "Server Messages",
+            self.focus == PaneKind::ServerMsgs,
+            &self.search,
+        );
     }
 
-    fn process_command(&mut self, tx_input: &std::sync::mpsc::Sender<CommandType>) -> Result<bool> {
+    fn process_command(&mut self, line: &str, tx_input: &std::sync::mpsc::Sender<TargetedCommand>) -> Result<()> {
         // Skip if input is empty
-        if self.input.is_empty() {
-            return Ok(false);
+        if line.trim().is_empty() {
+            return Ok(());
         }
 
-        // Check for command prefix
-        if let Some(cmd) = self.input.split_whitespace().next() {
-            let args = self.input.trim_start_matches(cmd).trim().to_string();
-            
-            // Match command to CommandType
-            let command = match cmd.to_lowercase().as_str() {
-                "chat" => Some(CommandType::Chat(args)),
-                "goto" => Some(CommandType::Goto(args)),
-                "mobkillaura" => {
-                    let mut enabled = false;
-                    if args == "on" {
-                        enabled = true;
-                    } else if args == "off" {
-                        enabled = false;
+        // `help` is answered right here, the bot never sees it
+        let mut words = line.split_whitespace();
+        if words.next().is_some_and(|cmd| cmd.eq_ignore_ascii_case("help")) {
+            match words.next() {
+                Some(topic) => match command::find_spec(&topic.to_lowercase()) {
+                    Some(spec) => self.log(format!("{} - {}", spec.usage, spec.help)),
+                    None => self.log(format!("error: unknown command `{topic}`")),
+                },
+                None => {
+                    for spec in command::COMMANDS {
+                        self.log(format!("{} - {}", spec.usage, spec.help));
                     }
-                    Some(CommandType::Mobkillaura(enabled))
+                    self.log("prefix a command with @bot, @group or @all to pick who runs it".to_string());
                 }
-                "mine" => Some(CommandType::Mine(args)),
-                // Add more command mappings here as needed
-                _ => None,
-            };
-
-            
-            // Process if valid command was found
-            if let Some(command) = command {
-                tx_input.send(command)?;
-
             }
+            return Ok(());
         }
-        
-        Ok(false)
+
+        let command = TargetedCommand::parse(line, self.default_target())?;
+        tx_input.send(command)?;
+        Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn draw_log(frame: &mut Frame, area: Rect, log: &mut Scrollback, title: &str, focused: bool, search: &str) {
+    let mut title = if log.follow || log.offset() == 0 {
+        title.to_string()
+    } else {
+        format!("{title} ({} newer lines)", log.offset())
+    };
+    if !search.is_empty() {
+        title.push_str(&format!(" [{} matching /{search}]", log.matches(search)));
+    }
+    let border = if focused { Style::default().fg(Color::Yellow) } else { Style::default() };
+    let rows = usize::from(area.height.saturating_sub(2));
+    let (selected, lines) = log.visible(rows);
+    let items: Vec<ListItem> = lines.map(|line| ListItem::new(highlight(line, search))).collect();
+    let list = List::new(items)
+        .block(Block::bordered().border_style(border).title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, area, &mut ListState::default().with_selected(selected));
+
+    // details of the selected line over the lower half of the pane
+    let Some(line) = log.selected() else {
+        return;
+    };
+    let details = if line.details.is_empty() { vec!["no extra data".to_string()] } else { line.details.clone() };
+    let height = (details.len() as u16 + 2).min(area.height / 2).max(3);
+    let popup = Rect { y: area.bottom().saturating_sub(height), height, ..area };
+    let text = Text::from(details.into_iter().map(Line::from).collect::<Vec<_>>());
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().border_style(border).title("Details")),
+        popup,
+    );
+}
@@ -0,0 +1,201 @@
+// Minecraft chat components to ratatui lines. azalea 0.12 drops hoverEvent and
+// clickEvent while parsing chat, so the details of a line are limited to what
+// it does keep: the sender, translation keys and the style of each part.
+
+use azalea::{chat::ChatPacket, FormattedText};
+use azalea_chat::{
+    style::Style as ChatStyle,
+    translatable_component::{StringOrComponent, TranslatableComponent},
+};
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use super::scrollback::LogLine;
+
+/// Shown instead of obfuscated text, like the scrambled glyphs of the game
+const OBFUSCATED_GLYPHS: &[char] = &['#', '$', '%', '&', '?', '@', '*', '+'];
+/// Stands in for the arguments of a translation, from the private use area
+/// so no real message has it
+const ARG_MARKER: char = '\u{E000}';
+
+/// A chat message with its styles and the metadata shown when it is selected
+pub fn to_log_line(chat: &ChatPacket) -> LogLine {
+    let message = chat.message();
+    let mut details = Vec::new();
+    if let Some(sender) = chat.sender() {
+        details.push(format!("sender: {sender}"));
+    }
+    if let Some(uuid) = chat.sender_uuid() {
+        details.push(format!("sender uuid: {uuid}"));
+    }
+    if chat.is_whisper() {
+        details.push("whisper".to_string());
+    }
+    describe(&message, &mut details);
+    LogLine::new(to_line(&message), details)
+}
+
+/// Every component as a span, children inherit the style of their parent
+pub fn to_line(text: &FormattedText) -> Line<'static> {
+    let mut spans = Vec::new();
+    push_spans(text, &ChatStyle::default(), &mut spans);
+    Line::from(spans)
+}
+
+fn push_spans(text: &FormattedText, parent: &ChatStyle, spans: &mut Vec<Span<'static>>) {
+    let base = text.get_base();
+    let mut style = if base.style.reset { ChatStyle::default() } else { parent.clone() };
+    style.apply(&base.style);
+
+    match text {
+        FormattedText::Text(component) => push_text(component.text.clone(), &style, spans),
+        FormattedText::Translatable(component) => push_translation(component, &style, spans),
+    }
+    for sibling in &base.siblings {
+        push_spans(sibling, &style, spans);
+    }
+}
+
+fn push_text(text: String, style: &ChatStyle, spans: &mut Vec<Span<'static>>) {
+    if !text.is_empty() {
+        let text = if style.obfuscated == Some(true) { obfuscate(&text) } else { text };
+        spans.push(Span::styled(text, to_style(style)));
+    }
+}
+
+/// The key's English translation without the siblings, which the caller
+/// adds, and every argument as spans of its own with its own style
+fn push_translation(component: &TranslatableComponent, style: &ChatStyle, spans: &mut Vec<Span<'static>>) {
+    // markers go in for the arguments, so the template around them can be
+    // split off while `read` still does the `%s` and `%1$s` matching
+    let args = (0..component.args.len()).map(|i| StringOrComponent::String(format!("{ARG_MARKER}{i}{ARG_MARKER}")));
+    let template = TranslatableComponent::new(component.key.clone(), args.collect());
+    let Ok(read) = template.read() else {
+        push_text(component.key.clone(), style, spans);
+        return;
+    };
+    let mut translated = read.text.clone();
+    for sibling in &read.base.siblings {
+        if let FormattedText::Text(part) = sibling {
+            translated.push_str(&part.text);
+        }
+    }
+    for (i, part) in translated.split(ARG_MARKER).enumerate() {
+        if i % 2 == 0 {
+            push_text(part.to_string(), style, spans);
+            continue;
+        }
+        match part.parse::<usize>().ok().and_then(|arg| component.args.get(arg)) {
+            Some(StringOrComponent::String(arg)) => push_text(arg.clone(), style, spans),
+            Some(StringOrComponent::FormattedText(arg)) => push_spans(arg, style, spans),
+            None => {}
+        }
+    }
+}
+
+fn obfuscate(text: &str) -> String {
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c.is_whitespace() { c } else { OBFUSCATED_GLYPHS[(i * 7 + c as usize) % OBFUSCATED_GLYPHS.len()] }
+        })
+        .collect()
+}
+
+fn to_style(chat: &ChatStyle) -> Style {
+    let mut style = Style::default();
+    if let Some(color) = &chat.color {
+        style = style.fg(to_color(color.name.as_deref(), color.value));
+    }
+    for (set, modifier) in [
+        (chat.bold, Modifier::BOLD),
+        (chat.italic, Modifier::ITALIC),
+        (chat.underlined, Modifier::UNDERLINED),
+        (chat.strikethrough, Modifier::CROSSED_OUT),
+    ] {
+        if set == Some(true) {
+            style = style.add_modifier(modifier);
+        }
+    }
+    style
+}
+
+/// The 16 named colors map onto the terminal palette so they follow its
+/// theme, hex colors are drawn as they are
+fn to_color(name: Option<&str>, rgb: u32) -> Color {
+    match name.map(str::to_ascii_uppercase).as_deref() {
+        Some("BLACK") => Color::Black,
+        Some("DARK_BLUE") => Color::Blue,
+        Some("DARK_GREEN") => Color::Green,
+        Some("DARK_AQUA") => Color::Cyan,
+        Some("DARK_RED") => Color::Red,
+        Some("DARK_PURPLE") => Color::Magenta,
+        Some("GOLD") => Color::Yellow,
+        Some("GRAY") => Color::Gray,
+        Some("DARK_GRAY") => Color::DarkGray,
+        Some("BLUE") => Color::LightBlue,
+        Some("GREEN") => Color::LightGreen,
+        Some("AQUA") => Color::LightCyan,
+        Some("RED") => Color::LightRed,
+        Some("LIGHT_PURPLE") => Color::LightMagenta,
+        Some("YELLOW") => Color::LightYellow,
+        Some("WHITE") => Color::White,
+        _ => Color::Rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8),
+    }
+}
+
+/// One line per translation and styled part, for the details view
+fn describe(text: &FormattedText, details: &mut Vec<String>) {
+    let base = text.get_base();
+    match text {
+        FormattedText::Text(component) => {
+            let style = describe_style(&base.style);
+            if !style.is_empty() && !component.text.is_empty() {
+                details.push(format!("{:?}: {style}", component.text));
+            }
+        }
+        FormattedText::Translatable(component) => {
+            let args = component
+                .args
+                .iter()
+                .map(|arg| match arg {
+                    StringOrComponent::String(s) => format!("{s:?}"),
+                    StringOrComponent::FormattedText(text) => format!("{:?}", text.to_string()),
+                })
+                .collect::<Vec<_>>();
+            details.push(format!("translate {} [{}]", component.key, args.join(", ")));
+            for arg in &component.args {
+                if let StringOrComponent::FormattedText(arg) = arg {
+                    describe(arg, details);
+                }
+            }
+        }
+    }
+    for sibling in &base.siblings {
+        describe(sibling, details);
+    }
+}
+
+fn describe_style(style: &ChatStyle) -> String {
+    let mut parts = Vec::new();
+    if let Some(color) = &style.color {
+        match &color.name {
+            Some(name) => parts.push(name.to_lowercase()),
+            None => parts.push(format!("#{:06X}", color.value)),
+        }
+    }
+    for (set, name) in [
+        (style.bold, "bold"),
+        (style.italic, "italic"),
+        (style.underlined, "underlined"),
+        (style.strikethrough, "strikethrough"),
+        (style.obfuscated, "obfuscated"),
+    ] {
+        if let Some(set) = set {
+            parts.push(if set { name.to_string() } else { format!("not {name}") });
+        }
+    }
+    parts.join(" ")
+}
@@ -0,0 +1,244 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+/// The command line of the TUI, with readline-style editing and history
+pub struct Input {
+    text: String,
+    /// Cursor position in chars
+    cursor: usize,
+    history: History,
+}
+
+impl Input {
+    pub fn new(history_file: Option<PathBuf>, history_size: usize) -> Self {
+        Self { text: String::new(), cursor: 0, history: History::load(history_file, history_size) }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_index(&self, char_idx: usize) -> usize {
+        self.text.char_indices().map(|(i, _)| i).nth(char_idx).unwrap_or(self.text.len())
+    }
+
+    /// The text left of the cursor, what Tab completes
+    pub fn before_cursor(&self) -> &str {
+        &self.text[..self.byte_index(self.cursor)]
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let idx = self.byte_index(self.cursor);
+        self.text.insert(idx, c);
+        self.cursor += 1;
+    }
+
+    /// Swaps the text from byte `start` up to the cursor for `replacement`
+    pub fn replace_before_cursor(&mut self, start: usize, replacement: &str) {
+        let end = self.byte_index(self.cursor);
+        self.text.replace_range(start..end, replacement);
+        self.cursor = self.text[..start + replacement.len()].chars().count();
+    }
+
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.remove_chars(self.cursor - 1, self.cursor);
+            self.cursor -= 1;
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.cursor < self.len() {
+            self.remove_chars(self.cursor, self.cursor + 1);
+        }
+    }
+
+    fn remove_chars(&mut self, from: usize, to: usize) {
+        let (from, to) = (self.byte_index(from), self.byte_index(to));
+        self.text.replace_range(from..to, "");
+    }
+
+    pub fn left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    /// Start of the word left of the cursor, skipping spaces first
+    fn word_start(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<_>>();
+        let mut idx = self.cursor;
+        while idx > 0 && chars[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        while idx > 0 && !chars[idx - 1].is_whitespace() {
+            idx -= 1;
+        }
+        idx
+    }
+
+    /// End of the word right of the cursor, skipping spaces first
+    fn word_end(&self) -> usize {
+        let chars = self.text.chars().collect::<Vec<_>>();
+        let mut idx = self.cursor;
+        while idx < chars.len() && chars[idx].is_whitespace() {
+            idx += 1;
+        }
+        while idx < chars.len() && !chars[idx].is_whitespace() {
+            idx += 1;
+        }
+        idx
+    }
+
+    pub fn word_left(&mut self) {
+        self.cursor = self.word_start();
+    }
+
+    pub fn word_right(&mut self) {
+        self.cursor = self.word_end();
+    }
+
+    pub fn delete_word_back(&mut self) {
+        let start = self.word_start();
+        self.remove_chars(start, self.cursor);
+        self.cursor = start;
+    }
+
+    pub fn delete_word_forward(&mut self) {
+        let end = self.word_end();
+        self.remove_chars(self.cursor, end);
+    }
+
+    /// Shows the previous history entry, keeping the line being typed for
+    /// when Down comes back to it
+    pub fn history_prev(&mut self) {
+        if let Some(entry) = self.history.prev(&self.text) {
+            self.set_text(entry);
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if let Some(entry) = self.history.next() {
+            self.set_text(entry);
+        }
+    }
+
+    fn set_text(&mut self, text: String) {
+        self.text = text;
+        self.cursor = self.len();
+    }
+
+    /// Clears the line and returns it, adding it to the history
+    pub fn submit(&mut self) -> String {
+        let text = std::mem::take(&mut self.text);
+        self.cursor = 0;
+        self.history.push(&text);
+        text
+    }
+}
+
+/// Submitted lines, oldest first, saved to a file as they are entered
+struct History {
+    entries: Vec<String>,
+    /// Entry Up/Down is on, `entries.len()` while on the line being typed
+    pos: usize,
+    /// The line being typed before Up was pressed
+    draft: String,
+    file: Option<PathBuf>,
+    size: usize,
+}
+
+/// Server commands that carry a password, lines with them are never kept so
+/// `chat /login <password>` doesn't end up on disk
+const SECRET_COMMANDS: &[&str] = &["/login", "/l", "/register", "/reg", "/changepassword", "/changepw"];
+
+fn is_secret(line: &str) -> bool {
+    line.split_whitespace().any(|word| SECRET_COMMANDS.iter().any(|command| word.eq_ignore_ascii_case(command)))
+}
+
+impl History {
+    fn load(file: Option<PathBuf>, size: usize) -> Self {
+        let lines = file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|text| text.lines().filter(|line| !line.trim().is_empty()).map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let mut entries = lines.iter().filter(|line| !is_secret(line)).cloned().collect::<Vec<_>>();
+        if entries.len() > size {
+            entries.drain(..entries.len() - size);
+        }
+        // keep the file from growing forever, and drop passwords saved by
+        // older versions
+        if size > 0
+            && entries.len() < lines.len()
+            && let Some(path) = &file
+            && let Ok(mut out) = File::create(path)
+        {
+            for entry in &entries {
+                let _ = writeln!(out, "{entry}");
+            }
+        }
+        Self { pos: entries.len(), entries, draft: String::new(), file, size }
+    }
+
+    fn push(&mut self, line: &str) {
+        self.pos = self.entries.len();
+        // a size of 0 turns the history off, file included
+        if self.size == 0 {
+            return;
+        }
+        if line.trim().is_empty() || is_secret(line) || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > self.size {
+            self.entries.remove(0);
+        }
+        self.pos = self.entries.len();
+        if let Some(path) = &self.file
+            && let Ok(mut out) = OpenOptions::new().create(true).append(true).open(path)
+        {
+            let _ = writeln!(out, "{line}");
+        }
+    }
+
+    fn prev(&mut self, current: &str) -> Option<String> {
+        if self.pos == 0 {
+            return None;
+        }
+        if self.pos == self.entries.len() {
+            self.draft = current.to_string();
+        }
+        self.pos -= 1;
+        Some(self.entries[self.pos].clone())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        if self.pos >= self.entries.len() {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.entries.get(self.pos).cloned().unwrap_or_else(|| std::mem::take(&mut self.draft)))
+    }
+}
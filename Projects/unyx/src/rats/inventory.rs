@@ -0,0 +1,173 @@
+use azalea::inventory::Player;
+use ratatui::{
+    crossterm::event::KeyCode,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Cell, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::azal::inventory::{InventoryAction, InventoryView, ItemView, SlotRef};
+
+const COLUMNS: usize = 9;
+const EMPTY_ARMOR: [&str; 5] = ["head", "chest", "legs", "feet", "offhand"];
+
+/// Where the cursor of the inventory grid is, and the first slot of a swap
+/// waiting for its second one
+#[derive(Default)]
+pub struct InventoryCursor {
+    row: usize,
+    col: usize,
+    marked: Option<SlotRef>,
+}
+
+impl InventoryCursor {
+    /// Moves the cursor or turns a key into an action for the bot
+    pub fn key(&mut self, code: KeyCode, view: &InventoryView) -> Option<InventoryAction> {
+        let rows = sections(view).into_iter().flat_map(|(_, rows)| rows).collect::<Vec<_>>();
+        self.clamp(&rows);
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.row += 1,
+            KeyCode::Left | KeyCode::Char('h') => self.col = self.col.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.col += 1,
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let here = rows[self.row][self.col];
+                return match self.marked.take() {
+                    Some(marked) => Some(InventoryAction::Swap(marked, here)),
+                    None => {
+                        self.marked = Some(here);
+                        None
+                    }
+                };
+            }
+            KeyCode::Char('d') => return Some(InventoryAction::Drop(rows[self.row][self.col])),
+            KeyCode::Char('s') => return Some(InventoryAction::QuickMove(rows[self.row][self.col])),
+            KeyCode::Char(c @ '1'..='9') => return Some(InventoryAction::SelectHotbar(c as u8 - b'1')),
+            _ => {}
+        }
+        self.clamp(&rows);
+        None
+    }
+
+    /// Drops a pending swap, false when there was none
+    pub fn cancel(&mut self) -> bool {
+        self.marked.take().is_some()
+    }
+
+    /// Keeps the cursor on the grid, which shrinks when a container closes
+    fn clamp(&mut self, rows: &[Vec<SlotRef>]) {
+        self.row = self.row.min(rows.len() - 1);
+        self.col = self.col.min(rows[self.row].len() - 1);
+    }
+}
+
+/// Title and rows of slots of every part of the grid, top to bottom
+fn sections(view: &InventoryView) -> Vec<(String, Vec<Vec<SlotRef>>)> {
+    let rows = |slots: Vec<SlotRef>| slots.chunks(COLUMNS).map(<[SlotRef]>::to_vec).collect::<Vec<_>>();
+    let mut sections = Vec::new();
+    if let Some((title, slots)) = &view.container {
+        sections.push((title.clone(), rows((0..slots.len()).map(SlotRef::Container).collect())));
+    }
+    let armor = Player::ARMOR_SLOTS.chain([Player::OFFHAND_SLOT]).map(SlotRef::Player).collect();
+    sections.push(("Armor and offhand".to_string(), vec![armor]));
+    sections.push((
+        "Inventory".to_string(),
+        rows(Player::INVENTORY_WITHOUT_HOTBAR_SLOTS.map(SlotRef::Player).collect()),
+    ));
+    sections.push(("Hotbar".to_string(), vec![Player::HOTBAR_SLOTS.map(SlotRef::Player).collect()]));
+    sections
+}
+
+fn item_at(view: &InventoryView, slot: SlotRef) -> &ItemView {
+    let item = match slot {
+        SlotRef::Player(idx) => view.player.get(idx),
+        SlotRef::Container(idx) => view.container.as_ref().and_then(|(_, slots)| slots.get(idx)),
+    };
+    item.unwrap_or(&None)
+}
+
+fn describe(slot: SlotRef, item: &ItemView) -> String {
+    let place = match slot {
+        SlotRef::Player(idx) => format!("slot {idx}"),
+        SlotRef::Container(idx) => format!("container slot {idx}"),
+    };
+    match item {
+        Some((name, count)) => format!("{place}: {name} x{count}"),
+        None => format!("{place}: empty"),
+    }
+}
+
+/// The grid of one bot, `None` before it is in a world
+pub fn draw_inventory(frame: &mut Frame, area: Rect, view: Option<&InventoryView>, cursor: &mut InventoryCursor) {
+    let block = Block::bordered().title("Inventory").border_style(Style::default().fg(Color::Yellow));
+    let Some(view) = view else {
+        frame.render_widget(Paragraph::new(Line::from("not in a world yet").dim()).block(block), area);
+        return;
+    };
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let sections = sections(view);
+    cursor.clamp(&sections.iter().flat_map(|(_, rows)| rows.clone()).collect::<Vec<_>>());
+    let constraints = sections
+        .iter()
+        .map(|(_, rows)| Constraint::Length(rows.len() as u16 + 2))
+        .chain([Constraint::Length(1), Constraint::Min(0)]);
+    let areas = Layout::vertical(constraints).split(inner);
+
+    let hotbar_selected = Player::HOTBAR_SLOTS.start() + usize::from(view.selected_hotbar);
+    let mut row_idx = 0;
+    let mut current = None;
+    for ((title, rows), area) in sections.iter().zip(areas.iter()) {
+        let mut table_rows = Vec::new();
+        for row in rows {
+            let cells = row.iter().enumerate().map(|(col, slot)| {
+                let item = item_at(view, *slot);
+                let mut style = Style::default();
+                let text = match item {
+                    Some((name, count)) if *count > 1 => format!("{name} x{count}"),
+                    Some((name, _)) => name.clone(),
+                    None => match slot {
+                        SlotRef::Player(idx) if title.starts_with("Armor") => {
+                            style = style.fg(Color::DarkGray);
+                            let armor = Player::ARMOR_SLOTS.chain([Player::OFFHAND_SLOT]);
+                            armor.zip(EMPTY_ARMOR).find(|(armor, _)| armor == idx).map_or("", |(_, name)| name).to_string()
+                        }
+                        _ => {
+                            style = style.fg(Color::DarkGray);
+                            "·".to_string()
+                        }
+                    },
+                };
+                if *slot == SlotRef::Player(hotbar_selected) {
+                    style = style.fg(Color::Green).add_modifier(Modifier::BOLD);
+                }
+                if cursor.marked == Some(*slot) {
+                    style = style.bg(Color::Yellow).fg(Color::Black);
+                }
+                if row_idx == cursor.row && col == cursor.col {
+                    style = style.add_modifier(Modifier::REVERSED);
+                    current = Some((*slot, item.clone()));
+                }
+                Cell::from(text).style(style)
+            });
+            table_rows.push(Row::new(cells.collect::<Vec<_>>()));
+            row_idx += 1;
+        }
+        let table = Table::new(table_rows, [Constraint::Ratio(1, COLUMNS as u32); COLUMNS])
+            .block(Block::bordered().title(title.as_str()));
+        frame.render_widget(table, *area);
+    }
+
+    let details_area = areas[sections.len()];
+    let details = match (current, cursor.marked) {
+        (Some((slot, item)), Some(marked)) => {
+            format!("{}, swapping with {}", describe(slot, &item), describe(marked, item_at(view, marked)))
+        }
+        (Some((slot, item)), None) => describe(slot, &item),
+        (None, _) => String::new(),
+    };
+    frame.render_widget(Paragraph::new(details), details_area);
+}
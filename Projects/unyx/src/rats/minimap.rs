@@ -0,0 +1,184 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::azal::minimap::{MarkerKind, Minimap, Ore, Terrain};
+
+/// Every cell is two characters wide, so the map is not squashed by the
+/// terminal font
+const CELL_WIDTH: u16 = 2;
+/// Blocks above the feet from which the top of a column is drawn bold
+const HIGH: i32 = 3;
+/// Blocks below the feet from which the top of a column is drawn dim
+const LOW: i32 = -6;
+pub const MIN_SCALE: u32 = 1;
+pub const MAX_SCALE: u32 = 8;
+
+const BROWN: Color = Color::Rgb(150, 100, 60);
+
+fn glyph(terrain: Terrain) -> (char, Style) {
+    let (c, color) = match terrain {
+        Terrain::Unloaded | Terrain::Empty => (' ', Color::Reset),
+        Terrain::Floor => ('.', Color::DarkGray),
+        Terrain::Water => ('~', Color::Blue),
+        Terrain::Lava => ('~', Color::Red),
+        Terrain::Grass => ('"', Color::Green),
+        Terrain::Leaves => ('*', Color::Green),
+        Terrain::Dirt => ('%', BROWN),
+        Terrain::Sand => (':', Color::LightYellow),
+        Terrain::Stone => ('#', Color::Gray),
+        Terrain::Wood => ('H', BROWN),
+        Terrain::Snow => ('=', Color::White),
+        Terrain::Crops => (',', Color::LightGreen),
+        Terrain::Ore(ore) => ('$', ore_color(ore)),
+        Terrain::Other => ('+', Color::DarkGray),
+    };
+    (c, Style::default().fg(color))
+}
+
+fn ore_color(ore: Ore) -> Color {
+    match ore {
+        Ore::Coal => Color::DarkGray,
+        Ore::Copper => Color::LightRed,
+        Ore::Iron | Ore::Quartz => Color::White,
+        Ore::Gold => Color::Yellow,
+        Ore::Redstone => Color::Red,
+        Ore::Lapis => Color::Blue,
+        Ore::Emerald => Color::LightGreen,
+        Ore::Diamond => Color::LightCyan,
+        Ore::Debris => Color::Magenta,
+    }
+}
+
+fn marker_style(kind: MarkerKind) -> Style {
+    let color = match kind {
+        MarkerKind::Player => Color::LightCyan,
+        MarkerKind::Monster => Color::LightRed,
+        MarkerKind::Animal => Color::LightGreen,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+/// Arrow for the way the bot faces, north is up
+fn arrow(yaw: f32) -> char {
+    const ARROWS: [char; 8] = ['↓', '↙', '←', '↖', '↑', '↗', '→', '↘'];
+    ARROWS[((yaw.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+/// Two letters for a marker, monsters and players capitalized
+fn marker_text(kind: MarkerKind, label: &str) -> String {
+    let mut chars = label.chars().filter(|c| c.is_alphanumeric());
+    let first = chars.next().unwrap_or('?');
+    let second = chars.next().unwrap_or(' ');
+    match kind {
+        MarkerKind::Animal => format!("{first}{second}"),
+        _ => format!("{}{second}", first.to_ascii_uppercase()),
+    }
+}
+
+fn legend() -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut entry = |text: &str, style: Style, name: &str| {
+        spans.push(Span::styled(text.to_string(), style));
+        spans.push(Span::raw(format!(" {name}  ")));
+    };
+    for (terrain, name) in [
+        (Terrain::Water, "water"),
+        (Terrain::Lava, "lava"),
+        (Terrain::Grass, "grass"),
+        (Terrain::Leaves, "leaves"),
+        (Terrain::Dirt, "dirt"),
+        (Terrain::Sand, "sand"),
+        (Terrain::Stone, "stone"),
+        (Terrain::Wood, "wood"),
+        (Terrain::Snow, "snow"),
+        (Terrain::Crops, "crops"),
+        (Terrain::Floor, "floor"),
+        (Terrain::Other, "other"),
+    ] {
+        let (c, style) = glyph(terrain);
+        entry(&c.to_string(), style, name);
+    }
+    for (ore, name) in [(Ore::Coal, "coal"), (Ore::Iron, "iron"), (Ore::Gold, "gold"), (Ore::Diamond, "diamond")] {
+        entry("$", Style::default().fg(ore_color(ore)), name);
+    }
+    entry("@", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD), "bot");
+    entry("Zo", marker_style(MarkerKind::Monster), "monster");
+    entry("co", marker_style(MarkerKind::Animal), "animal");
+    entry("Pl", marker_style(MarkerKind::Player), "player");
+    spans
+}
+
+/// The map of one bot, `None` until the first one came in
+pub fn draw_minimap(frame: &mut Frame, area: Rect, map: Option<&Minimap>) {
+    let Some(map) = map else {
+        let text = Line::from("waiting for the bot to send a map").dim();
+        frame.render_widget(Paragraph::new(text).block(Block::bordered().title("Map")), area);
+        return;
+    };
+    let mode = match map.request.slice {
+        Some(offset) => format!("slice y={}", map.center.y + offset),
+        None => "surface".to_string(),
+    };
+    let title = format!("Map 1:{} {mode} at {} {} {}", map.request.scale, map.center.x, map.center.y, map.center.z);
+    let block = Block::bordered().title(title).border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let legend = Line::from(legend());
+    let legend_rows = (legend.width() as u16).div_ceil(inner.width.max(1)).min(inner.height / 3);
+    let legend = Paragraph::new(legend).wrap(Wrap { trim: true });
+    let [map_area, legend_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(legend_rows)]).areas(inner);
+    frame.render_widget(legend, legend_area);
+
+    // crop the map around the bot when the pane is smaller than it
+    let size = map.cells.len();
+    let middle = size / 2;
+    let cols = usize::from(map_area.width / CELL_WIDTH).min(size);
+    let rows = usize::from(map_area.height).min(size);
+    let (first_col, first_row) = (middle - cols / 2, middle - rows / 2);
+
+    let mut cells = map.cells[first_row..first_row + rows]
+        .iter()
+        .map(|row| {
+            row[first_col..first_col + cols]
+                .iter()
+                .map(|cell| {
+                    let (c, mut style) = glyph(cell.terrain);
+                    if map.request.slice.is_none() {
+                        if cell.height >= HIGH {
+                            style = style.add_modifier(Modifier::BOLD);
+                        } else if cell.height <= LOW {
+                            style = style.add_modifier(Modifier::DIM);
+                        }
+                    }
+                    (format!("{c}{c}"), style)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut put = |row: usize, col: usize, text: String, style: Style| {
+        if let Some(cell) = row.checked_sub(first_row).zip(col.checked_sub(first_col)).and_then(|(row, col)| cells.get_mut(row)?.get_mut(col)) {
+            *cell = (text, style);
+        }
+    };
+    for marker in &map.markers {
+        put(marker.row, marker.col, marker_text(marker.kind, &marker.label), marker_style(marker.kind));
+    }
+    put(
+        middle,
+        middle,
+        format!("@{}", arrow(map.yaw)),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    );
+
+    let lines = cells
+        .into_iter()
+        .map(|row| Line::from(row.into_iter().map(|(text, style)| Span::styled(text, style)).collect::<Vec<_>>()))
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(lines), map_area);
+}
@@ -0,0 +1,214 @@
+use std::collections::VecDeque;
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+const MATCH_STYLE: Style = Style::new().fg(Color::Black).bg(Color::Yellow);
+
+/// A styled line of a log pane, with its text for searching and whatever
+/// extra data the details view shows
+#[derive(Clone)]
+pub struct LogLine {
+    pub plain: String,
+    pub line: Line<'static>,
+    pub details: Vec<String>,
+}
+
+impl LogLine {
+    pub fn new(line: Line<'static>, details: Vec<String>) -> Self {
+        let plain = line.spans.iter().map(|span| span.content.as_ref()).collect();
+        Self { plain, line, details }
+    }
+}
+
+impl From<String> for LogLine {
+    fn from(text: String) -> Self {
+        Self { line: Line::raw(text.clone()), plain: text, details: Vec::new() }
+    }
+}
+
+/// Lines of one log pane, dropping the oldest once full, along with where the
+/// pane is scrolled to
+pub struct Scrollback {
+    lines: VecDeque<LogLine>,
+    capacity: usize,
+    /// Lines between the bottom of the view and the newest line
+    offset: usize,
+    /// Rows the pane had on the last draw, keeps scrolling from running past
+    /// the oldest line
+    height: usize,
+    /// Keep the newest line in view as lines come in
+    pub follow: bool,
+    /// Line whose details are shown
+    selected: Option<usize>,
+}
+
+impl Scrollback {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity.min(1024)),
+            capacity: capacity.max(1),
+            offset: 0,
+            height: 1,
+            follow: true,
+            selected: None,
+        }
+    }
+
+    pub fn push(&mut self, line: impl Into<LogLine>) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+            self.selected = self.selected.and_then(|idx| idx.checked_sub(1));
+        }
+        self.lines.push_back(line.into());
+        // keep the same lines in view while the user reads back
+        if !self.follow {
+            self.offset = (self.offset + 1).min(self.max_offset());
+        }
+    }
+
+    fn max_offset(&self) -> usize {
+        self.lines.len().saturating_sub(self.height)
+    }
+
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.offset = (self.offset + rows).min(self.max_offset());
+        self.follow = self.offset == 0 && self.follow;
+    }
+
+    /// Scrolling back to the bottom follows the tail again
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_sub(rows);
+        if self.offset == 0 {
+            self.follow = true;
+        }
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.offset = 0;
+        }
+    }
+
+    /// How far back the view is, 0 at the newest line
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Lines that fit in `height` rows at the current scroll position, and
+    /// which of them is selected
+    pub fn visible(&mut self, height: usize) -> (Option<usize>, impl Iterator<Item = &LogLine>) {
+        self.height = height.max(1);
+        self.offset = self.offset.min(self.max_offset());
+        let end = self.lines.len() - self.offset;
+        let start = end.saturating_sub(self.height);
+        let selected = self.selected.filter(|idx| (start..end).contains(idx)).map(|idx| idx - start);
+        (selected, self.lines.range(start..end))
+    }
+
+    /// Selects the bottom line of the view, or clears the selection
+    pub fn toggle_selection(&mut self) {
+        self.selected = match self.selected {
+            Some(_) => None,
+            None => self.lines.len().checked_sub(1 + self.offset),
+        };
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.selected.is_some()
+    }
+
+    /// Moves the selection a line up or down, scrolling to keep it in view
+    pub fn move_selection(&mut self, older: bool) {
+        let Some(idx) = self.selected else {
+            return;
+        };
+        let idx = if older { idx.saturating_sub(1) } else { (idx + 1).min(self.lines.len() - 1) };
+        self.selected = Some(idx);
+        let bottom = self.lines.len() - 1 - self.offset;
+        if idx > bottom {
+            self.offset = self.lines.len() - 1 - idx;
+        } else if idx + self.height <= bottom {
+            self.offset = self.lines.len() - idx - self.height;
+        }
+        self.follow = self.offset == 0;
+    }
+
+    pub fn selected(&self) -> Option<&LogLine> {
+        self.lines.get(self.selected?)
+    }
+
+    /// Scrolls to the closest line holding `query`, looking back from the
+    /// bottom line of the view, or forward when `older` is false. With
+    /// `skip_current` the bottom line itself is not a match, for jumping on
+    /// to the next one.
+    pub fn find(&mut self, query: &str, older: bool, skip_current: bool) -> bool {
+        if query.is_empty() || self.lines.is_empty() {
+            return false;
+        }
+        let bottom = self.lines.len() - 1 - self.offset;
+        let skip = usize::from(skip_current);
+        let found = if older {
+            (0..=bottom).rev().skip(skip).find(|idx| contains_ignore_case(&self.lines[*idx].plain, query))
+        } else {
+            (bottom + skip..self.lines.len()).find(|idx| contains_ignore_case(&self.lines[*idx].plain, query))
+        };
+        let Some(idx) = found else {
+            return false;
+        };
+        self.offset = self.lines.len() - 1 - idx;
+        self.follow = self.offset == 0;
+        true
+    }
+
+    /// Lines holding `query`
+    pub fn matches(&self, query: &str) -> usize {
+        if query.is_empty() {
+            return 0;
+        }
+        self.lines.iter().filter(|line| contains_ignore_case(&line.plain, query)).count()
+    }
+}
+
+fn contains_ignore_case(line: &str, query: &str) -> bool {
+    line.to_ascii_lowercase().contains(&query.to_ascii_lowercase())
+}
+
+/// `line` with every match of `query` highlighted on top of its own styles,
+/// ignoring ASCII case
+pub fn highlight<'a>(line: &'a LogLine, query: &str) -> Line<'a> {
+    if query.is_empty() {
+        return line.line.clone();
+    }
+    // ASCII lowercasing keeps every byte offset, so they index the spans too
+    let lower = line.plain.to_ascii_lowercase();
+    let query = query.to_ascii_lowercase();
+    let matches = lower.match_indices(&query).map(|(start, m)| start..start + m.len()).collect::<Vec<_>>();
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    for span in &line.line.spans {
+        let content = span.content.as_ref();
+        let end = pos + content.len();
+        let mut cut = pos;
+        for range in matches.iter().filter(|range| range.start < end && range.end > pos) {
+            let (start, stop) = (range.start.max(pos), range.end.min(end));
+            if start > cut {
+                spans.push(Span::styled(&content[cut - pos..start - pos], span.style));
+            }
+            spans.push(Span::styled(&content[start - pos..stop - pos], span.style.patch(MATCH_STYLE)));
+            cut = stop;
+        }
+        if cut < end {
+            spans.push(Span::styled(&content[cut - pos..], span.style));
+        }
+        pos = end;
+    }
+    Line::from(spans)
+}
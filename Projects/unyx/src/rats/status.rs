@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+
+use crate::azal::status::BotStatus;
+
+/// Details of one bot, or a short summary of every bot on the swarm tab
+pub fn draw_status(frame: &mut Frame, area: Rect, statuses: &BTreeMap<String, BotStatus>, bot: Option<&str>) {
+    let lines = match bot {
+        Some(bot) => match statuses.get(bot) {
+            Some(status) => bot_lines(status),
+            None => vec![Line::from("not in a world yet").dim()],
+        },
+        None if statuses.is_empty() => vec![Line::from("no bot in a world yet").dim()],
+        None => statuses.values().flat_map(summary_lines).collect(),
+    };
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title("Status")), area);
+}
+
+fn bot_lines(status: &BotStatus) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    if !status.online {
+        lines.push(Line::from("offline, last seen with").fg(Color::Red));
+    }
+    let mut health = vec![
+        "Health ".into(),
+        Span::styled(format!("{:.1}/20", status.health), health_style(status.health)),
+    ];
+    if status.absorption > 0.0 {
+        health.push(Span::styled(format!(" +{:.1}", status.absorption), Style::default().fg(Color::Yellow)));
+    }
+    lines.push(Line::from(health));
+    lines.push(Line::from(vec![
+        "Food   ".into(),
+        Span::styled(format!("{}/20", status.food), food_style(status.food)),
+        format!(" sat {:.1}", status.saturation).into(),
+    ]));
+    let pos = status.position;
+    lines.push(Line::from(format!("XYZ    {:.1} {:.1} {:.1}", pos.x, pos.y, pos.z)));
+    lines.push(Line::from(format!(
+        "Facing {} ({:.0}/{:.0})",
+        facing(status.yaw),
+        status.yaw.rem_euclid(360.0),
+        status.pitch
+    )));
+    lines.push(Line::from(format!("World  {}", status.dimension)));
+    lines.push(Line::from(format!(
+        "XP     level {} ({:.0}%)",
+        status.experience.level,
+        status.experience.progress * 100.0
+    )));
+    lines.push(Line::from(match &status.held_item {
+        Some((name, count)) if *count > 1 => format!("Hand   {name} x{count}"),
+        Some((name, _)) => format!("Hand   {name}"),
+        None => "Hand   empty".to_string(),
+    }));
+    let mut modules = vec![Span::raw("Mods  ")];
+    for (name, enabled) in &status.modules {
+        let style = if *enabled { Style::default().fg(Color::Green) } else { Style::default().fg(Color::DarkGray) };
+        modules.push(Span::styled(format!(" {name}"), style));
+    }
+    lines.push(Line::from(modules));
+    if let Some(danger) = &status.danger {
+        lines.push(Line::from(vec!["Danger ".into(), Span::styled(danger.clone(), danger_style())]));
+    }
+    lines.push(Line::from(format!("Task   {}", status.task.as_deref().unwrap_or("idle"))));
+    lines
+}
+
+fn summary_lines(status: &BotStatus) -> Vec<Line<'static>> {
+    let name = if status.online {
+        Span::styled(status.name.clone(), Style::default().add_modifier(Modifier::BOLD))
+    } else {
+        Span::styled(format!("{} (offline)", status.name), Style::default().fg(Color::Red))
+    };
+    let mut lines = vec![
+        Line::from(name),
+        Line::from(vec![
+            " ♥ ".into(),
+            Span::styled(format!("{:.0}", status.health), health_style(status.health)),
+            " food ".into(),
+            Span::styled(status.food.to_string(), food_style(status.food)),
+            format!(" {}", status.dimension).into(),
+        ]),
+        Line::from(format!(
+            " {:.0} {:.0} {:.0} {}",
+            status.position.x,
+            status.position.y,
+            status.position.z,
+            status.task.as_deref().unwrap_or("idle")
+        )),
+    ];
+    if let Some(danger) = &status.danger {
+        lines.push(Line::from(vec![" ".into(), Span::styled(danger.clone(), danger_style())]));
+    }
+    lines
+}
+
+fn health_style(health: f32) -> Style {
+    match health {
+        h if h <= 6.0 => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        h if h <= 12.0 => Style::default().fg(Color::Yellow),
+        _ => Style::default().fg(Color::Green),
+    }
+}
+
+fn danger_style() -> Style {
+    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+}
+
+fn food_style(food: u32) -> Style {
+    match food {
+        0..=6 => Style::default().fg(Color::Red),
+        7..=14 => Style::default().fg(Color::Yellow),
+        _ => Style::default().fg(Color::Green),
+    }
+}
+
+/// Compass direction of a yaw, 0 faces south like in the game
+fn facing(yaw: f32) -> &'static str {
+    const DIRECTIONS: [&str; 8] = ["S", "SW", "W", "NW", "N", "NE", "E", "SE"];
+    let idx = ((yaw.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8;
+    DIRECTIONS[idx]
+}